@@ -0,0 +1,205 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Condvar, Mutex,
+        atomic::{AtomicI64, Ordering},
+    },
+    task::Waker,
+    time::Duration,
+};
+
+use crate::{
+    android::{android_helper_class_loader, get_android_context},
+    jni_with_env,
+    receiver::Intent,
+};
+
+use jni::{
+    Env,
+    errors::Error,
+    objects::{JClass, JObject, JString},
+    refs::{Global, Reference},
+};
+
+const EXTRA_TARGET_INTENT: &str = "rust.jniminhelper.target_intent";
+const EXTRA_TOKEN: &str = "rust.jniminhelper.result_token";
+
+jni::bind_java_type! {
+    ResultActivity => "rust.jniminhelper.ResultActivity",
+    type_map = {
+        Intent => "android.content.Intent",
+    },
+    native_methods {
+        fn native_on_activity_result(token: jlong, result_code: jint, data: Intent),
+        fn native_on_cancelled(token: jlong),
+    },
+}
+
+/// Result of the activity started by [start_activity_for_result].
+#[derive(Debug)]
+pub enum ActivityResult {
+    /// `resultCode` (`RESULT_OK` is -1, `RESULT_CANCELED` is 0) and the optional data intent
+    /// returned by the target activity.
+    Finished {
+        result_code: i32,
+        data: Option<Global<Intent<'static>>>,
+    },
+    /// The trampoline activity is destroyed without a result, or the target activity
+    /// cannot be started.
+    Cancelled,
+}
+
+static NEXT_TOKEN: AtomicI64 = AtomicI64::new(1);
+static PENDING_REQUESTS: Mutex<Option<HashMap<i64, Arc<RequestState>>>> = Mutex::new(None);
+
+// Shared by `ActivityResultRequest` and the native callbacks, which take it by the token.
+#[derive(Default)]
+struct RequestState {
+    result: Mutex<Option<ActivityResult>>,
+    cond: Condvar,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl RequestState {
+    fn send(&self, result: ActivityResult) {
+        self.result.lock().unwrap().replace(result);
+        self.cond.notify_all();
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+fn take_pending(token: i64) -> Option<Arc<RequestState>> {
+    PENDING_REQUESTS.lock().unwrap().as_mut()?.remove(&token)
+}
+
+/// Starts the activity of `intent` through the trampoline activity `rust.jniminhelper.ResultActivity`,
+/// which calls `startActivityForResult()` and delivers the result back to Rust. Multiple requests
+/// can be pending at the same time, they are distinguished by tokens.
+///
+/// Using this function *requires* the activity `rust.jniminhelper.ResultActivity` to be declared
+/// in the `AndroidManifest.xml`, and this activity must be compiled in the package's `classes.dex`
+/// file. `ResultActivity.java` can be found in the source code (check [crate::PermissionRequest]).
+pub fn start_activity_for_result(intent: &JObject<'_>) -> Result<ActivityResultRequest, Error> {
+    crate::check_helper_class("ResultActivity")?;
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::Relaxed);
+    let state = jni_with_env(|env| {
+        let target = env.as_cast::<Intent>(intent)?;
+        let loader = jni::refs::LoaderContext::Loader(android_helper_class_loader()?);
+        let _ = ResultActivityAPI::get(env, &loader)?;
+        let cls_result = ResultActivity::lookup_class(env, &loader)?;
+
+        let context = get_android_context();
+        let trampoline = Intent::new(env)?;
+        use std::ops::Deref;
+        trampoline.set_class(env, context, AsRef::<JClass>::as_ref(&cls_result.deref()))?;
+        let extra_target = JString::new(env, EXTRA_TARGET_INTENT)?;
+        trampoline.put_extra_parcelable(env, extra_target, &*target)?;
+        let extra_token = JString::new(env, EXTRA_TOKEN)?;
+        trampoline.put_extra_long(env, extra_token, token)?;
+
+        let state = Arc::new(RequestState::default());
+        PENDING_REQUESTS
+            .lock()
+            .unwrap()
+            .get_or_insert_default()
+            .insert(token, state.clone());
+
+        context.start_activity(env, &trampoline)?;
+        Ok(state)
+    })
+    .inspect_err(|_| {
+        let _ = take_pending(token);
+    })?;
+    Ok(ActivityResultRequest { token, state })
+}
+
+/// Pending request created by [start_activity_for_result]; dropping it before the result is
+/// received discards the result.
+pub struct ActivityResultRequest {
+    token: i64,
+    state: Arc<RequestState>,
+}
+
+impl ActivityResultRequest {
+    /// Blocks on waiting the activity result.
+    ///
+    /// Warning: Blocking in the `android_main()` thread will block the request's completion if it
+    /// depends on event processing in this thread (check your glue crate like `android_activity`).
+    pub fn wait(self) -> ActivityResult {
+        let result = self.state.result.lock().unwrap();
+        let mut result = self.state.cond.wait_while(result, |r| r.is_none()).unwrap();
+        result.take().unwrap()
+    }
+
+    /// Blocks on waiting the activity result until the timeout elapses; returns `None` on
+    /// timeout, then it may be waited again. Check [ActivityResultRequest::wait] for the warning.
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<ActivityResult> {
+        let result = self.state.result.lock().unwrap();
+        let (mut result, _) = self
+            .state
+            .cond
+            .wait_timeout_while(result, timeout, |r| r.is_none())
+            .unwrap();
+        result.take()
+    }
+}
+
+impl Drop for ActivityResultRequest {
+    fn drop(&mut self) {
+        let _ = take_pending(self.token);
+    }
+}
+
+#[cfg(feature = "futures")]
+impl std::future::Future for ActivityResultRequest {
+    type Output = ActivityResult;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        // the waker is registered with the result locked, so `send()` cannot be missed
+        let mut result = self.state.result.lock().unwrap();
+        if let Some(result) = result.take() {
+            return std::task::Poll::Ready(result);
+        }
+        self.state.waker.lock().unwrap().replace(cx.waker().clone());
+        std::task::Poll::Pending
+    }
+}
+
+impl ResultActivityNativeInterface for ResultActivityAPI {
+    type Error = Error;
+    fn native_on_activity_result<'local>(
+        env: &mut Env<'local>,
+        _this: ResultActivity<'local>,
+        token: jni::sys::jlong,
+        result_code: jni::sys::jint,
+        data: Intent<'local>,
+    ) -> ::std::result::Result<(), Self::Error> {
+        let Some(sender) = take_pending(token) else {
+            warn!("ResultActivity: no pending request for token {token}.");
+            return Ok(());
+        };
+        let data = if data.is_null() {
+            None
+        } else {
+            Some(env.new_global_ref(data)?)
+        };
+        sender.send(ActivityResult::Finished { result_code, data });
+        Ok(())
+    }
+
+    fn native_on_cancelled<'local>(
+        _env: &mut Env<'local>,
+        _this: ResultActivity<'local>,
+        token: jni::sys::jlong,
+    ) -> ::std::result::Result<(), Self::Error> {
+        if let Some(sender) = take_pending(token) {
+            sender.send(ActivityResult::Cancelled);
+        }
+        Ok(())
+    }
+}
@@ -1,26 +1,42 @@
 use crate::{
+    bindings::{JInputStream, JLocale},
+    content::ContentResolver,
     jni_with_env,
+    prefs::SharedPreferences,
     receiver::{AndroidBroadcastReceiver, Intent, IntentFilter},
+    service::ServiceConnection,
 };
 use jni::{
     Env, bind_java_type,
     errors::Error,
     jni_sig, jni_str,
     objects::{JClassLoader, JObject, JString},
-    refs::Global,
+    refs::{Global, Reference},
 };
 
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
     str::FromStr,
-    sync::OnceLock,
+    sync::{OnceLock, RwLock},
 };
 
 const DEX_DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/classes.dex"));
 
-pub(crate) fn get_helper_class_loader() -> Result<&'static JClassLoader<'static>, Error> {
+/// Gets the class loader of the dex embedded by `jni-min-helper`, which includes helper classes
+/// in the package `rust.jniminhelper` and extra Java classes given at build time (check the
+/// description of `JNI_MIN_HELPER_EXTRA_SRC` in the crate's `README.md`).
+pub fn android_helper_class_loader() -> Result<&'static JClassLoader<'static>, Error> {
     static CLASS_LOADER: OnceLock<Global<JClassLoader<'static>>> = OnceLock::new();
     if CLASS_LOADER.get().is_none() {
+        let api_level = android_api_level();
+        if api_level < crate::HELPER_DEX_MIN_API {
+            warn!(
+                "The embedded dex requires Android API level {}, but the device is {api_level}.",
+                crate::HELPER_DEX_MIN_API
+            );
+            return Err(Error::UnsupportedVersion);
+        }
         let loader = jni_with_env(|env| {
             let dex_loader = get_android_context()
                 .get_class_loader(env)?
@@ -36,9 +52,15 @@ bind_java_type! {
     pub(crate) AndroidContext => "android.content.Context",
     type_map = {
         JFile => "java.io.File",
+        PackageManager => "android.content.pm.PackageManager",
+        AndroidResources => "android.content.res.Resources",
         AndroidBroadcastReceiver => "android.content.BroadcastReceiver",
         Intent => "android.content.Intent",
         IntentFilter => "android.content.IntentFilter",
+        ServiceConnection => "android.content.ServiceConnection",
+        ContentResolver => "android.content.ContentResolver",
+        SharedPreferences => "android.content.SharedPreferences",
+        AssetManager => "android.content.res.AssetManager",
     },
     methods {
         fn get_files_dir() -> JFile,
@@ -46,24 +68,267 @@ bind_java_type! {
         fn get_code_cache_dir() -> JFile, // API level >= 21
         fn get_dir(name: JString, mode: jint) -> JFile,
         fn get_class_loader() -> JClassLoader,
+        fn get_application_context() -> AndroidContext,
         fn get_package_name() -> JString,
+        fn get_package_manager() -> PackageManager,
+        fn get_resources() -> AndroidResources,
+        fn get_assets() -> AssetManager,
+        fn get_content_resolver() -> ContentResolver,
+        fn get_system_service(name: JString) -> JObject,
+        fn get_shared_preferences(name: JString, mode: jint) -> SharedPreferences,
         fn register_receiver {
             name = "registerReceiver",
             sig = (receiver: AndroidBroadcastReceiver, filter: IntentFilter) -> Intent,
         },
         fn unregister_receiver(receiver: AndroidBroadcastReceiver),
         fn check_self_permission(permission: JString) -> jint,
+        fn check_calling_or_self_permission(permission: JString) -> jint,
         fn start_activity(intent: Intent) -> (),
+        fn bind_service(service: Intent, conn: ServiceConnection, flags: jint) -> jboolean,
+        fn unbind_service(conn: ServiceConnection),
     }
 }
 
 bind_java_type! {
     pub(crate) JFile => "java.io.File",
+    constructors {
+        fn new(pathname: JString),
+    },
     methods {
         fn get_absolute_path() -> JString,
     }
 }
 
+bind_java_type! {
+    pub(crate) AssetManager => "android.content.res.AssetManager",
+    type_map = {
+        JInputStream => "java.io.InputStream",
+    },
+    methods {
+        fn open(file_name: JString) -> JInputStream,
+        fn list(path: JString) -> JString[],
+    }
+}
+
+bind_java_type! {
+    pub(crate) AndroidResources => "android.content.res.Resources",
+    type_map = {
+        AndroidConfiguration => "android.content.res.Configuration",
+        AndroidDisplayMetrics => "android.util.DisplayMetrics",
+    },
+    methods {
+        fn get_string(id: jint) -> JString,
+        fn get_identifier(name: JString, def_type: JString, def_package: JString) -> jint,
+        fn get_configuration() -> AndroidConfiguration,
+        fn get_display_metrics() -> AndroidDisplayMetrics,
+    }
+}
+
+bind_java_type! {
+    pub(crate) AndroidDisplayMetrics => "android.util.DisplayMetrics",
+    fields {
+        width_pixels {
+            sig = jint,
+            name = "widthPixels",
+            get = width_pixels,
+        },
+        height_pixels {
+            sig = jint,
+            name = "heightPixels",
+            get = height_pixels,
+        },
+        density {
+            sig = jfloat,
+            get = density,
+        },
+        density_dpi {
+            sig = jint,
+            name = "densityDpi",
+            get = density_dpi,
+        },
+    },
+}
+
+bind_java_type! {
+    AndroidConfiguration => "android.content.res.Configuration",
+    type_map = {
+        JLocale => "java.util.Locale",
+    },
+    fields {
+        locale: JLocale,
+    },
+}
+
+// Method IDs are resolved on the API initialization, so it's separated from `AndroidConfiguration`.
+bind_java_type! {
+    AndroidConfigurationApi24 => "android.content.res.Configuration",
+    type_map = {
+        AndroidLocaleList => "android.os.LocaleList",
+    },
+    methods {
+        fn get_locales() -> AndroidLocaleList,
+    },
+}
+
+bind_java_type! {
+    AndroidLocaleList => "android.os.LocaleList",
+    type_map = {
+        JLocale => "java.util.Locale",
+    },
+    methods {
+        fn size() -> jint,
+        fn get(index: jint) -> JLocale,
+    },
+}
+
+// Reads BCP-47 tags of locales in the current configuration, in the order of preference.
+pub(crate) fn android_current_locales(env: &mut Env<'_>) -> Result<Vec<String>, Error> {
+    let config = get_android_context()
+        .get_resources(env)?
+        .get_configuration(env)?;
+    let mut tags = Vec::new();
+    if android_feature_available(AndroidFeature::LocaleList) {
+        // Safety: `AndroidConfiguration` and `AndroidConfigurationApi24` both map to `android.content.res.Configuration`.
+        let config: jni::refs::Cast<'_, '_, AndroidConfigurationApi24> =
+            unsafe { env.as_cast_unchecked(&config) };
+        let locales = config.get_locales(env)?;
+        for i in 0..locales.size(env)? {
+            tags.push(locales.get(env, i)?.to_language_tag(env)?.to_string());
+        }
+    } else {
+        let locale = config.locale(env)?;
+        if !locale.is_null() {
+            tags.push(locale.to_language_tag(env)?.to_string());
+        }
+    }
+    Ok(tags)
+}
+
+bind_java_type! {
+    pub(crate) PackageManager => "android.content.pm.PackageManager",
+    type_map = {
+        PackageInfo => "android.content.pm.PackageInfo",
+        PermissionInfo => "android.content.pm.PermissionInfo",
+    },
+    methods {
+        // deprecated in API level 33, but still available
+        fn get_package_info(package_name: JString, flags: jint) -> PackageInfo,
+        fn get_permission_info(permission_name: JString, flags: jint) -> PermissionInfo,
+    }
+}
+
+bind_java_type! {
+    pub(crate) PackageInfo => "android.content.pm.PackageInfo",
+    fields {
+        version_name {
+            sig = JString,
+            name = "versionName",
+            get = version_name,
+        },
+        // deprecated in API level 28
+        version_code {
+            sig = jint,
+            name = "versionCode",
+            get = version_code,
+        },
+        // `null` unless `GET_PERMISSIONS` is set in the flags
+        requested_permissions {
+            sig = JString[],
+            name = "requestedPermissions",
+            get = requested_permissions,
+        },
+    },
+}
+
+bind_java_type! {
+    pub(crate) PermissionInfo => "android.content.pm.PermissionInfo",
+    fields {
+        name {
+            sig = JString,
+            get = name,
+        },
+        group {
+            sig = JString,
+            get = group,
+        },
+        // deprecated in API level 28
+        protection_level {
+            sig = jint,
+            name = "protectionLevel",
+            get = protection_level,
+        },
+    },
+}
+
+// Bindings of members that are unavailable on older API levels. Method IDs are
+// resolved on the API initialization, so they cannot be placed in the bindings above.
+mod package_info_ext {
+    use super::{PackageInfo, PackageManager};
+    use crate::{AndroidFeature, android_feature_available};
+    use jni::{Env, errors::Error, objects::JString, refs::Cast};
+
+    jni::bind_java_type! {
+        PackageManagerApi33 => "android.content.pm.PackageManager",
+        type_map = {
+            PackageInfo => "android.content.pm.PackageInfo",
+            PackageInfoFlags => "android.content.pm.PackageManager$PackageInfoFlags",
+        },
+        methods {
+            fn get_package_info {
+                name = "getPackageInfo",
+                sig = (package_name: JString, flags: PackageInfoFlags) -> PackageInfo,
+            },
+        },
+    }
+
+    jni::bind_java_type! {
+        PackageInfoFlags => "android.content.pm.PackageManager$PackageInfoFlags",
+        methods {
+            static fn of(value: jlong) -> PackageInfoFlags,
+        },
+    }
+
+    jni::bind_java_type! {
+        PackageInfoApi28 => "android.content.pm.PackageInfo",
+        methods {
+            fn get_long_version_code() -> jlong,
+        },
+    }
+
+    impl<'local> PackageManager<'local> {
+        /// Calls `getPackageInfo`, choosing the overload available on the current API level.
+        pub fn get_package_info_compat<'env_local, 'local_0>(
+            &self,
+            env: &mut Env<'env_local>,
+            package_name: impl AsRef<JString<'local_0>>,
+            flags: i64,
+        ) -> Result<PackageInfo<'env_local>, Error> {
+            if android_feature_available(AndroidFeature::PackageInfoFlags) {
+                let flags = PackageInfoFlags::of(env, flags)?;
+                // Safety: `PackageManager` and `PackageManagerApi33` both map to `android.content.pm.PackageManager`.
+                let pm: Cast<'_, '_, PackageManagerApi33> = unsafe { env.as_cast_unchecked(self) };
+                pm.get_package_info(env, package_name, flags)
+            } else {
+                self.get_package_info(env, package_name, flags as i32)
+            }
+        }
+    }
+
+    impl<'local> PackageInfo<'local> {
+        /// Reads `getLongVersionCode()` on API level 28 and above, otherwise reads the
+        /// deprecated `versionCode` field.
+        pub fn long_version_code(&self, env: &mut Env<'_>) -> Result<i64, Error> {
+            if android_feature_available(AndroidFeature::LongVersionCode) {
+                // Safety: `PackageInfo` and `PackageInfoApi28` both map to `android.content.pm.PackageInfo`.
+                let info: Cast<'_, '_, PackageInfoApi28> = unsafe { env.as_cast_unchecked(self) };
+                info.get_long_version_code(env)
+            } else {
+                self.version_code(env).map(|c| c as i64)
+            }
+        }
+    }
+}
+
 bind_java_type! {
     InMemoryDexClassLoader => "dalvik.system.InMemoryDexClassLoader",
     constructors {
@@ -92,6 +357,66 @@ bind_java_type! {
             sig = jint,
             get = SDK_INT,
         },
+        #[allow(non_snake_case)]
+        static RELEASE {
+            sig = JString,
+            get = RELEASE,
+        },
+        #[allow(non_snake_case)]
+        static CODENAME {
+            sig = JString,
+            get = CODENAME,
+        },
+    },
+}
+
+bind_java_type! {
+    AndroidSystemProperties => "android.os.SystemProperties",
+    methods {
+        static fn get(key: JString) -> JString,
+    },
+}
+
+bind_java_type! {
+    AndroidBuild => "android.os.Build",
+    fields {
+        #[allow(non_snake_case)]
+        static MANUFACTURER {
+            sig = JString,
+            get = MANUFACTURER,
+        },
+        #[allow(non_snake_case)]
+        static MODEL {
+            sig = JString,
+            get = MODEL,
+        },
+        #[allow(non_snake_case)]
+        static DEVICE {
+            sig = JString,
+            get = DEVICE,
+        },
+        #[allow(non_snake_case)]
+        static CPU_ABI {
+            sig = JString,
+            get = CPU_ABI,
+        },
+        #[allow(non_snake_case)]
+        static CPU_ABI2 {
+            sig = JString,
+            get = CPU_ABI2,
+        },
+    },
+}
+
+// Field IDs are resolved on the API initialization, so it's separated from `AndroidBuild`.
+bind_java_type! {
+    AndroidBuildApi21 => "android.os.Build",
+    fields {
+        #[allow(non_snake_case)]
+        static SUPPORTED_ABIS {
+            sig = JString[],
+            get = SUPPORTED_ABIS,
+        },
     },
 }
 
@@ -109,13 +434,20 @@ pub trait DexClassLoader<'local> {
 impl<'local> DexClassLoader<'local> for JClassLoader<'local> {
     /// Creates a `dalvik.system.DexClassLoader` from given dex file data embeded at compile time,
     /// having the current loader as the parent loader. This function may do heavy operations.
+    ///
+    /// Returns `Error::ClassFormatError` if the data doesn't start with a valid dex header.
+    /// Below API level 26, failure of writing the dex file is logged and returned as
+    /// `Error::SecurityViolation` (permission denied) or `Error::JniCall`.
     fn load_dex(
         &self,
         env: &mut Env<'local>,
         dex_data: &'static [u8],
     ) -> Result<JClassLoader<'local>, Error> {
+        if !crate::dex_header_is_valid(dex_data) {
+            return Err(Error::ClassFormatError);
+        }
         let context = get_android_context();
-        if android_api_level() >= 26 {
+        if android_feature_available(AndroidFeature::InMemoryDex) {
             // Safety: dex_data is 'static and the `InMemoryDexClassLoader`` will not mutate it.
             // The data may be converted by `ConvertDexFilesToJavaArray()` and handled by the
             // created Java class loader, which shouldn't be freed before the class and its
@@ -142,7 +474,16 @@ impl<'local> DexClassLoader<'local> for JClassLoader<'local> {
             };
             let dex_name = format!("{dex_hash:016x}.dex");
             let dex_file_path = code_cache_path.join(dex_name);
-            std::fs::write(&dex_file_path, dex_data).unwrap(); // Note: this panics on failure
+            if let Err(e) = std::fs::write(&dex_file_path, dex_data) {
+                warn!("Failed to write {}: {e}", dex_file_path.display());
+                return Err(match e.kind() {
+                    std::io::ErrorKind::PermissionDenied => Error::SecurityViolation,
+                    std::io::ErrorKind::OutOfMemory => {
+                        Error::JniCall(jni::errors::JniError::NoMemory)
+                    }
+                    _ => Error::JniCall(jni::errors::JniError::Unknown),
+                });
+            }
             let dex_file_path = JString::new(env, dex_file_path.to_string_lossy())?;
 
             // creates the oats directory
@@ -171,8 +512,9 @@ pub fn android_context() -> &'static JObject<'static> {
     get_android_context().as_ref()
 }
 
+static ANDROID_CONTEXT: OnceLock<Global<AndroidContext<'static>>> = OnceLock::new();
+
 pub(crate) fn get_android_context() -> &'static AndroidContext<'static> {
-    static ANDROID_CONTEXT: OnceLock<Global<AndroidContext<'static>>> = OnceLock::new();
     let ctx = ANDROID_CONTEXT.get_or_init(|| {
         jni_with_env(|env| {
             let ctx_raw_global = ndk_context::android_context().context() as jni::sys::jobject;
@@ -185,21 +527,8 @@ pub(crate) fn get_android_context() -> &'static AndroidContext<'static> {
                 };
                 env.new_global_ref(ctx.as_ref())
             } else {
-                let th = get_activity_thread(env)?;
-                let app = env
-                    .call_method(
-                        &th,
-                        jni_str!("getApplication"),
-                        jni::jni_sig!(() -> android.app.Application),
-                        &[],
-                    )?
-                    .l()?;
-                let ctx = AndroidContext::cast_local(env, app)?;
-                let ctx = env.new_global_ref(ctx)?;
-                if ctx.is_null() {
-                    panic!("got null from ActivityThread.getApplication()");
-                }
-                Ok(ctx)
+                let app = get_current_application(env)?;
+                env.new_global_ref(app)
             }
         })
         .unwrap()
@@ -207,6 +536,64 @@ pub(crate) fn get_android_context() -> &'static AndroidContext<'static> {
     ctx.as_ref()
 }
 
+bind_java_type! {
+    pub(crate) AndroidActivity => "android.app.Activity",
+}
+
+// Checks if the current Android context is an activity rather than the application.
+pub(crate) fn context_is_activity(env: &mut Env<'_>) -> Result<bool, Error> {
+    let class = AndroidActivity::lookup_class(env, &jni::refs::LoaderContext::None)?;
+    let class: &jni::objects::JClass = &class;
+    env.is_instance_of(get_android_context(), class)
+}
+
+// Gets the application context (`Context.getApplicationContext()`), which lives as long as the
+// process even if the current Android context is an activity. It doesn't panic if the Android
+// context is not read yet; the application of the current `ActivityThread` is used in that case.
+pub(crate) fn get_application_context() -> Result<&'static AndroidContext<'static>, Error> {
+    static APP_CONTEXT: OnceLock<Global<AndroidContext<'static>>> = OnceLock::new();
+    if APP_CONTEXT.get().is_none() {
+        let app_ctx = jni_with_env(|env| {
+            let Some(ctx) = try_get_android_context() else {
+                let app = get_current_application(env)?;
+                return env.new_global_ref(app);
+            };
+            let app_ctx = ctx.get_application_context(env)?;
+            if app_ctx.is_null() {
+                env.new_global_ref(ctx)
+            } else {
+                env.new_global_ref(app_ctx)
+            }
+        })?;
+        let _ = APP_CONTEXT.set(app_ctx);
+    }
+    Ok(APP_CONTEXT.get().unwrap())
+}
+
+// Non-panicking accessor of the Android context stored by `get_android_context`, returns `None`
+// if it is not read yet (`ndk_context` panics if it is not initialized).
+pub(crate) fn try_get_android_context() -> Option<&'static AndroidContext<'static>> {
+    ANDROID_CONTEXT.get().map(|ctx| ctx.as_ref())
+}
+
+fn get_current_application<'a>(env: &mut Env<'a>) -> Result<AndroidContext<'a>, Error> {
+    let th = get_activity_thread(env)?;
+    let app = env
+        .call_method(
+            &th,
+            jni_str!("getApplication"),
+            jni::jni_sig!(() -> android.app.Application),
+            &[],
+        )?
+        .l()?;
+    if app.is_null() {
+        return Err(Error::NullPtr(
+            "got null from ActivityThread.getApplication()",
+        ));
+    }
+    AndroidContext::cast_local(env, app)
+}
+
 fn get_activity_thread<'a>(env: &mut Env<'a>) -> Result<JObject<'a>, Error> {
     env.call_static_method(
         jni_str!("android/app/ActivityThread"),
@@ -217,12 +604,164 @@ fn get_activity_thread<'a>(env: &mut Env<'a>) -> Result<JObject<'a>, Error> {
     .l()
 }
 
+/// Android platform features gated by the API level, check [android_feature_available].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AndroidFeature {
+    /// `Build.SUPPORTED_ABIS` (API level 21).
+    SupportedAbis,
+    /// `android.os.LocaleList` of multiple preferred locales (API level 24).
+    LocaleList,
+    /// Runtime permissions requested by `Activity.requestPermissions()` (API level 23).
+    RuntimePermissions,
+    /// `dalvik.system.InMemoryDexClassLoader` (API level 26).
+    InMemoryDex,
+    /// `android.app.NotificationChannel`, required for posting notifications (API level 26).
+    NotificationChannels,
+    /// `android.os.VibrationEffect` and `Vibrator.vibrate(VibrationEffect)` (API level 26).
+    VibrationEffect,
+    /// `PackageInfo.getLongVersionCode()` (API level 28).
+    LongVersionCode,
+    /// `WindowManager.getCurrentWindowMetrics()`, replacing the deprecated
+    /// `WindowManager.getDefaultDisplay()` (API level 30).
+    WindowMetrics,
+    /// `android.os.VibratorManager`, replacing the deprecated `VIBRATOR_SERVICE` (API level 31).
+    VibratorManager,
+    /// `RECEIVER_EXPORTED` and `RECEIVER_NOT_EXPORTED` flags of `Context.registerReceiver()`,
+    /// required for receivers of non-system broadcasts (API level 33).
+    ReceiverExportFlag,
+    /// `Intent.getParcelableExtra(String, Class)` (API level 33).
+    TypedParcelableExtra,
+    /// `PackageManager.getPackageInfo(String, PackageInfoFlags)` (API level 33).
+    PackageInfoFlags,
+}
+
+impl AndroidFeature {
+    /// Gets the minimum API level providing the feature.
+    pub const fn min_api_level(self) -> i32 {
+        match self {
+            Self::SupportedAbis => 21,
+            Self::RuntimePermissions => 23,
+            Self::LocaleList => 24,
+            Self::InMemoryDex | Self::NotificationChannels | Self::VibrationEffect => 26,
+            Self::LongVersionCode => 28,
+            Self::WindowMetrics => 30,
+            Self::VibratorManager => 31,
+            Self::ReceiverExportFlag | Self::TypedParcelableExtra | Self::PackageInfoFlags => 33,
+        }
+    }
+}
+
+/// Checks if the feature is available on the API level of the current Android OS.
+pub fn android_feature_available(feature: AndroidFeature) -> bool {
+    android_api_level() >= feature.min_api_level()
+}
+
 /// Gets the API level (SDK version) of the current Android OS.
 pub fn android_api_level() -> i32 {
     static API_LEVEL: OnceLock<i32> = OnceLock::new();
     *API_LEVEL.get_or_init(|| jni_with_env(|env| AndroidBuildVersion::SDK_INT(env)).unwrap())
 }
 
+/// Gets the user-visible version string of the Android system, e.g. `"14"`.
+pub fn android_os_release() -> &'static str {
+    static RELEASE: OnceLock<String> = OnceLock::new();
+    RELEASE.get_or_init(|| {
+        jni_with_env(|env| AndroidBuildVersion::RELEASE(env).map(|s| s.to_string())).unwrap()
+    })
+}
+
+/// Gets the development codename of the Android system, or `"REL"` for a release build.
+pub fn android_os_codename() -> &'static str {
+    static CODENAME: OnceLock<String> = OnceLock::new();
+    CODENAME.get_or_init(|| {
+        jni_with_env(|env| AndroidBuildVersion::CODENAME(env).map(|s| s.to_string())).unwrap()
+    })
+}
+
+/// Reads the Android system property like `ro.build.fingerprint` through the hidden API
+/// `android.os.SystemProperties.get()`. Returns `None` if the property is empty or not set,
+/// or if the hidden API is blocked on this device. Values are not cached.
+pub fn android_system_property(key: &str) -> Option<String> {
+    jni_with_env(|env| {
+        let key = JString::new(env, key)?;
+        let value = AndroidSystemProperties::get(env, key).inspect_err(|_| {
+            env.exception_clear();
+        })?;
+        Ok((!value.is_null()).then(|| value.to_string()))
+    })
+    .ok()
+    .flatten()
+    .filter(|s| !s.is_empty())
+}
+
+/// Device and system information returned by [android_device_info].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// `Build.MANUFACTURER`.
+    pub manufacturer: String,
+    /// `Build.MODEL`, the end-user-visible name.
+    pub model: String,
+    /// `Build.DEVICE`, the industrial design name.
+    pub device: String,
+    /// `Build.VERSION.RELEASE`, check [android_os_release].
+    pub release: String,
+    /// `Build.VERSION.SDK_INT`, check [android_api_level].
+    pub api_level: i32,
+    /// `Build.SUPPORTED_ABIS` in the order of preference, or non-empty values of
+    /// `Build.CPU_ABI` and `Build.CPU_ABI2` below API level 21.
+    pub supported_abis: Vec<String>,
+}
+
+impl std::fmt::Display for DeviceInfo {
+    /// Formats the information in a single line, suitable for log headers.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} ({}), Android {} (API {}), ABIs: {}",
+            self.manufacturer,
+            self.model,
+            self.device,
+            self.release,
+            self.api_level,
+            self.supported_abis.join(", ")
+        )
+    }
+}
+
+/// Gets the device and system information, which is read once and cached.
+pub fn android_device_info() -> &'static DeviceInfo {
+    static DEVICE_INFO: OnceLock<DeviceInfo> = OnceLock::new();
+    DEVICE_INFO.get_or_init(|| {
+        jni_with_env(|env| {
+            let supported_abis = if android_feature_available(AndroidFeature::SupportedAbis) {
+                let abis = AndroidBuildApi21::SUPPORTED_ABIS(env)?;
+                let mut supported_abis = Vec::new();
+                for i in 0..abis.len(env)? {
+                    supported_abis.push(abis.get_element(env, i)?.to_string());
+                }
+                supported_abis
+            } else {
+                [AndroidBuild::CPU_ABI(env)?, AndroidBuild::CPU_ABI2(env)?]
+                    .iter()
+                    .filter(|abi| !abi.is_null())
+                    .map(|abi| abi.to_string())
+                    .filter(|abi| !abi.is_empty())
+                    .collect()
+            };
+            Ok(DeviceInfo {
+                manufacturer: AndroidBuild::MANUFACTURER(env)?.to_string(),
+                model: AndroidBuild::MODEL(env)?.to_string(),
+                device: AndroidBuild::DEVICE(env)?.to_string(),
+                release: android_os_release().to_string(),
+                api_level: android_api_level(),
+                supported_abis,
+            })
+        })
+        .unwrap()
+    })
+}
+
 /// Gets the raw name of the current Android application, parsed from the package name.
 pub fn android_app_name() -> &'static str {
     static APP_NAME: OnceLock<String> = OnceLock::new();
@@ -248,6 +787,238 @@ pub fn android_app_package_name() -> &'static str {
     })
 }
 
+/// Gets the version name of the current Android application, declared as `versionName`
+/// in the `AndroidManifest.xml`. Returns `None` if it is not declared.
+pub fn android_app_version_name() -> Option<&'static str> {
+    static VERSION_NAME: OnceLock<Option<String>> = OnceLock::new();
+    VERSION_NAME
+        .get_or_init(|| {
+            jni_with_env(|env| {
+                let name = get_app_package_info(env)?.version_name(env)?;
+                Ok::<_, Error>((!name.is_null()).then(|| name.to_string()))
+            })
+            .unwrap()
+        })
+        .as_deref()
+}
+
+/// Gets the version code of the current Android application, declared as `versionCode`
+/// in the `AndroidManifest.xml`. The higher 32 bits (`versionCodeMajor`) are included
+/// on API level 28 and above.
+pub fn android_app_version_code() -> i64 {
+    static VERSION_CODE: OnceLock<i64> = OnceLock::new();
+    *VERSION_CODE.get_or_init(|| {
+        jni_with_env(|env| get_app_package_info(env)?.long_version_code(env)).unwrap()
+    })
+}
+
+fn get_app_package_info<'a>(env: &mut Env<'a>) -> Result<PackageInfo<'a>, Error> {
+    let context = get_android_context();
+    let package_name = context.get_package_name(env)?;
+    let package_info =
+        context
+            .get_package_manager(env)?
+            .get_package_info_compat(env, package_name, 0)?;
+    if package_info.is_null() {
+        return Err(Error::NullPtr(
+            "PackageManager.getPackageInfo() returned null",
+        ));
+    }
+    Ok(package_info)
+}
+
+/// Gets the localized string of the resource ID (`R.string.*`) from the application resources.
+/// A missing resource produces `Error::CaughtJavaException` of `Resources$NotFoundException`.
+pub fn android_get_string(res_id: i32) -> Result<String, Error> {
+    jni_with_env(|env| {
+        let resources = get_android_context().get_resources(env)?;
+        match resources.get_string(env, res_id) {
+            Ok(s) => Ok(s.to_string()),
+            Err(Error::JavaException) => env.exception_catch().and(Err(Error::JavaException)),
+            Err(e) => Err(e),
+        }
+    })
+}
+
+/// Gets the localized string resource by its name (like `app_name`) in the package of the
+/// current application. Returns `Error::FieldNotFound` if the resource is not found.
+///
+/// Note: looking up by identifier is slower than retrieving by resource ID.
+pub fn android_get_string_by_name(name: &str) -> Result<String, Error> {
+    let res_id = jni_with_env(|env| {
+        let resources = get_android_context().get_resources(env)?;
+        let res_name = JString::new(env, name)?;
+        let def_type = JString::new(env, "string")?;
+        let def_package = JString::new(env, android_app_package_name())?;
+        resources.get_identifier(env, res_name, def_type, def_package)
+    })?;
+    if res_id == 0 {
+        return Err(Error::FieldNotFound {
+            name: name.to_string(),
+            sig: "string resource".to_string(),
+        });
+    }
+    android_get_string(res_id)
+}
+
+/// Reads the whole file in the `assets` directory of the APK, like `data/config.json`.
+/// Returns `Error::FieldNotFound` if the asset doesn't exist.
+pub fn android_asset_bytes(path: &str) -> Result<Vec<u8>, Error> {
+    jni_with_env(|env| {
+        let assets = get_android_context().get_assets(env)?;
+        let file_name = JString::new(env, path)?;
+        let stream = match assets.open(env, file_name) {
+            Ok(stream) => stream,
+            Err(Error::JavaException) => {
+                return match env.exception_catch() {
+                    Err(Error::CaughtJavaException { name, .. })
+                        if name == "java.io.FileNotFoundException" =>
+                    {
+                        Err(Error::FieldNotFound {
+                            name: path.to_string(),
+                            sig: "asset".to_string(),
+                        })
+                    }
+                    Err(e) => Err(e),
+                    Ok(()) => Err(Error::JavaException),
+                };
+            }
+            Err(e) => return Err(e),
+        };
+        let data = stream.read_to_end(env);
+        let _ = stream.close(env);
+        data
+    })
+}
+
+/// Reads the asset file as an UTF-8 string, check [android_asset_bytes].
+/// Returns `Error::ParseFailed` if the content is not valid UTF-8.
+pub fn android_asset_string(path: &str) -> Result<String, Error> {
+    String::from_utf8(android_asset_bytes(path)?)
+        .map_err(|_| Error::ParseFailed(format!("asset `{path}` is not valid UTF-8")))
+}
+
+/// Lists names of files and subdirectories in the `assets` directory of the APK, like `data`;
+/// an empty string lists the root. The list is empty if the directory doesn't exist.
+pub fn android_asset_list(dir: &str) -> Result<Vec<String>, Error> {
+    jni_with_env(|env| {
+        let assets = get_android_context().get_assets(env)?;
+        let path = JString::new(env, dir.trim_end_matches('/'))?;
+        let names = assets.list(env, path)?;
+        if names.is_null() {
+            return Ok(Vec::new());
+        }
+        crate::jni_read_string_array(env, &names)
+    })
+}
+
+/// Gets the system service by its name (like `connectivity`, `power` or `clipboard`, check
+/// constants `*_SERVICE` of `android.content.Context`) from the application context.
+/// Returns `Error::NullPtr` if the service is not available on this device or user profile.
+///
+/// Services are cached by name on success; they don't need to be invalidated, because they are
+/// always looked up on the application context (not an activity that may be destroyed), and
+/// system service managers are singletons living as long as the process.
+pub fn android_system_service(name: &str) -> Result<Global<JObject<'static>>, Error> {
+    static SERVICES: RwLock<Option<HashMap<String, Global<JObject<'static>>>>> = RwLock::new(None);
+    jni_with_env(|env| {
+        if let Some(service) = SERVICES.read().unwrap().as_ref().and_then(|m| m.get(name)) {
+            return env.new_global_ref(service);
+        }
+        let service_name = JString::new(env, name)?;
+        let service = get_application_context()?.get_system_service(env, service_name)?;
+        if service.is_null() {
+            return Err(Error::NullPtr("Context.getSystemService() returned null"));
+        }
+        let service = env.new_global_ref(service)?;
+        let mut services = SERVICES.write().unwrap();
+        // the entry may be inserted by another thread, then the new global reference is dropped
+        let service = services
+            .get_or_insert_default()
+            .entry(name.to_string())
+            .or_insert(service);
+        env.new_global_ref(&*service)
+    })
+}
+
+fn android_typed_system_service<T: Reference>(name: &str) -> Result<Global<T::GlobalKind>, Error> {
+    let service = android_system_service(name)?;
+    jni_with_env(|env| env.new_cast_global_ref::<T>(service.as_obj()))
+}
+
+bind_java_type! {
+    pub AndroidConnectivityManager => "android.net.ConnectivityManager",
+    methods {
+        fn is_active_network_metered() -> jboolean,
+    },
+}
+
+bind_java_type! {
+    pub AndroidPowerManager => "android.os.PowerManager",
+    methods {
+        fn is_interactive() -> jboolean,
+    },
+}
+
+bind_java_type! {
+    pub AndroidNotificationManager => "android.app.NotificationManager",
+    methods {
+        fn cancel(id: jint),
+        fn cancel_all(),
+    },
+}
+
+bind_java_type! {
+    pub AndroidClipboardManager => "android.content.ClipboardManager",
+    methods {
+        fn has_primary_clip() -> jboolean,
+    },
+}
+
+bind_java_type! {
+    pub AndroidAlarmManager => "android.app.AlarmManager",
+    type_map = {
+        AndroidPendingIntent => "android.app.PendingIntent",
+    },
+    methods {
+        fn cancel(operation: AndroidPendingIntent),
+    },
+}
+
+bind_java_type! {
+    pub AndroidPendingIntent => "android.app.PendingIntent",
+    methods {
+        fn cancel(),
+    },
+}
+
+/// Gets `android.net.ConnectivityManager`, check [android_system_service].
+pub fn android_connectivity_manager() -> Result<Global<AndroidConnectivityManager<'static>>, Error>
+{
+    android_typed_system_service::<AndroidConnectivityManager>("connectivity")
+}
+
+/// Gets `android.os.PowerManager`, check [android_system_service].
+pub fn android_power_manager() -> Result<Global<AndroidPowerManager<'static>>, Error> {
+    android_typed_system_service::<AndroidPowerManager>("power")
+}
+
+/// Gets `android.app.NotificationManager`, check [android_system_service].
+pub fn android_notification_manager() -> Result<Global<AndroidNotificationManager<'static>>, Error>
+{
+    android_typed_system_service::<AndroidNotificationManager>("notification")
+}
+
+/// Gets `android.content.ClipboardManager`, check [android_system_service].
+pub fn android_clipboard_manager() -> Result<Global<AndroidClipboardManager<'static>>, Error> {
+    android_typed_system_service::<AndroidClipboardManager>("clipboard")
+}
+
+/// Gets `android.app.AlarmManager`, check [android_system_service].
+pub fn android_alarm_manager() -> Result<Global<AndroidAlarmManager<'static>>, Error> {
+    android_typed_system_service::<AndroidAlarmManager>("alarm")
+}
+
 /// Returns the absolute path to the directory holding application files. No permissions
 /// are required for the calling app to read or write files under the returned path.
 pub fn android_app_files_dir() -> &'static Path {
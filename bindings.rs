@@ -1,4 +1,13 @@
-use jni::bind_java_type;
+use jni::{
+    Env, bind_java_type,
+    errors::Error,
+    objects::{
+        JClass, JList, JObject, JObjectArray, JPrimitiveArray, JStackTraceElement, JString,
+        JThrowable, TypeArray,
+    },
+    refs::{Global, LoaderContext, Reference},
+    sys::{jbyte, jdouble, jfloat, jint, jlong, jshort},
+};
 
 bind_java_type! {
     pub(crate) JProxy => "java.lang.reflect.Proxy",
@@ -11,6 +20,8 @@ bind_java_type! {
             interfaces: JClass[],
             invoc_hdl: JInvocationHandler
         ) -> JObject,
+        static fn is_proxy_class(cl: JClass) -> jboolean,
+        static fn get_invocation_handler(proxy: JObject) -> JInvocationHandler,
     },
 }
 
@@ -22,6 +33,7 @@ bind_java_type! {
     pub JMethod => "java.lang.reflect.Method",
     methods {
         fn equals(arg0: JObject) -> jboolean,
+        fn invoke(obj: JObject, args: JObject[]) -> JObject,
         fn get_name() -> JString,
         fn get_parameter_types() -> JClass[],
         fn get_return_type() -> JClass,
@@ -189,6 +201,845 @@ bind_java_type! {
     },
 }
 
+bind_java_type! {
+    pub JAtomicInteger => "java.util.concurrent.atomic.AtomicInteger",
+    type_map = {
+        JNumber => "java.lang.Number",
+    },
+    constructors {
+        fn new(initial_value: jint),
+    },
+    methods {
+        fn get() -> jint,
+        fn set(new_value: jint),
+        fn get_and_set(new_value: jint) -> jint,
+        fn compare_and_set(expected_value: jint, new_value: jint) -> jboolean,
+        fn add_and_get(delta: jint) -> jint,
+        fn increment_and_get() -> jint,
+    },
+    is_instance_of = {
+        number: JNumber,
+    },
+}
+
+bind_java_type! {
+    pub JAtomicLong => "java.util.concurrent.atomic.AtomicLong",
+    type_map = {
+        JNumber => "java.lang.Number",
+    },
+    constructors {
+        fn new(initial_value: jlong),
+    },
+    methods {
+        fn get() -> jlong,
+        fn set(new_value: jlong),
+        fn get_and_set(new_value: jlong) -> jlong,
+        fn compare_and_set(expected_value: jlong, new_value: jlong) -> jboolean,
+        fn add_and_get(delta: jlong) -> jlong,
+        fn increment_and_get() -> jlong,
+    },
+    is_instance_of = {
+        number: JNumber,
+    },
+}
+
+bind_java_type! {
+    /// `java.util.Optional`, available on Android API level 24 and above.
+    pub JOptional => "java.util.Optional",
+    methods {
+        fn is_present() -> jboolean,
+        fn get() -> JObject,
+        static fn of(value: JObject) -> JOptional,
+        static fn of_nullable(value: JObject) -> JOptional,
+        static fn empty() -> JOptional,
+    },
+}
+
+bind_java_type! {
+    /// `java.util.function.Function`, available on Android API level 24 and above.
+    ///
+    /// This and [JConsumer], [JSupplier] are for invoking lambdas provided by Java code;
+    /// `env.as_cast()` checks the object's type before the call.
+    ///
+    /// ```
+    /// use jni::{
+    ///     objects::{JObject, JString},
+    ///     refs::LoaderContext,
+    ///     strings::JNIString,
+    /// };
+    /// use jni_min_helper::*;
+    /// use std::sync::{Arc, Mutex};
+    /// jni_init_vm_for_unit_test();
+    /// jni_with_env(|env| {
+    ///     let func = JFunction::identity(env)?;
+    ///     let arg = JString::new(env, "abc")?;
+    ///     let out = func.apply(env, &arg)?;
+    ///     assert!(env.is_same_object(&out, &arg)?);
+    ///
+    ///     let supplier = DynamicProxy::build(
+    ///         env,
+    ///         &LoaderContext::None,
+    ///         &[JNIString::from("java.util.function.Supplier")],
+    ///         |env, _, _| Ok(JString::new(env, "supplied")?.into()),
+    ///     )?;
+    ///     let out = env.as_cast::<JSupplier>(&*supplier)?.get(env)?;
+    ///     assert_eq!(jni_stringify(env, &out)?, "supplied");
+    ///
+    ///     let accepted = Arc::new(Mutex::new(String::new()));
+    ///     let accepted_1 = accepted.clone();
+    ///     let consumer = DynamicProxy::build(
+    ///         env,
+    ///         &LoaderContext::None,
+    ///         &[JNIString::from("java.util.function.Consumer")],
+    ///         move |env, _, args| {
+    ///             let arg = args.get_element(env, 0)?;
+    ///             *accepted_1.lock().unwrap() = jni_stringify(env, &arg)?;
+    ///             Ok(JObject::null())
+    ///         },
+    ///     )?;
+    ///     env.as_cast::<JConsumer>(&*consumer)?.accept(env, &arg)?;
+    ///     assert_eq!(*accepted.lock().unwrap(), "abc");
+    ///
+    ///     // not a `Function`
+    ///     assert!(env.as_cast::<JFunction>(&*supplier).is_err());
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub JFunction => "java.util.function.Function",
+    methods {
+        fn apply(t: JObject) -> JObject,
+        static fn identity() -> JFunction,
+    },
+}
+
+bind_java_type! {
+    /// `java.util.function.Consumer`, available on Android API level 24 and above.
+    pub JConsumer => "java.util.function.Consumer",
+    methods {
+        fn accept(t: JObject),
+    },
+}
+
+bind_java_type! {
+    /// `java.util.function.Supplier`, available on Android API level 24 and above.
+    pub JSupplier => "java.util.function.Supplier",
+    methods {
+        fn get() -> JObject,
+    },
+}
+
+bind_java_type! {
+    JArrayList => "java.util.ArrayList",
+    constructors {
+        fn new(initial_capacity: jint),
+    },
+}
+
+bind_java_type! {
+    pub(crate) JSystem => "java.lang.System",
+    methods {
+        static fn get_property(key: JString) -> JString,
+        static fn identity_hash_code(x: JObject) -> jint,
+        static fn gc(),
+        static fn run_finalization(),
+    },
+}
+
+bind_java_type! {
+    pub(crate) JRuntime => "java.lang.Runtime",
+    methods {
+        static fn get_runtime() -> JRuntime,
+        fn total_memory() -> jlong,
+        fn free_memory() -> jlong,
+        fn max_memory() -> jlong,
+    },
+}
+
+bind_java_type! {
+    pub(crate) JLocale => "java.util.Locale",
+    methods {
+        static fn get_default() -> JLocale,
+        fn to_language_tag() -> JString,
+    },
+}
+
+bind_java_type! {
+    JField => "java.lang.reflect.Field",
+    methods {
+        fn get(obj: JObject) -> JObject,
+    },
+}
+
+bind_java_type! {
+    JObjects => "java.util.Objects",
+    methods {
+        static fn to_string(obj: JObject) -> JString,
+    },
+}
+
+bind_java_type! {
+    pub JInputStream => "java.io.InputStream",
+    methods {
+        fn read_into {
+            name = "read",
+            sig = (b: jbyte[], off: jint, len: jint) -> jint,
+        },
+        fn close(),
+    },
+}
+
+impl JInputStream<'_> {
+    const READ_CHUNK_SIZE: usize = 8192;
+
+    /// Reads all remaining bytes from the stream in chunks. It doesn't close the stream.
+    pub fn read_to_end(&self, env: &mut Env<'_>) -> Result<Vec<u8>, Error> {
+        let buf = env.new_byte_array(Self::READ_CHUNK_SIZE)?;
+        let mut chunk = vec![0; Self::READ_CHUNK_SIZE];
+        let mut data = Vec::new();
+        loop {
+            let len = self.read_into(env, &buf, 0, Self::READ_CHUNK_SIZE as jint)?;
+            if len < 0 {
+                break;
+            }
+            let len = len as usize;
+            buf.get_region(env, 0, &mut chunk[..len])?;
+            data.extend(chunk[..len].iter().map(|&b| b as u8));
+        }
+        Ok(data)
+    }
+}
+
+impl JBoolean<'_> {
+    /// Converts a loosely-typed object to `bool`, unlike the strict [JBoolean::value].
+    /// Accepts a `java.lang.Boolean`, a `java.lang.Number` (non-zero is `true`) or a
+    /// `java.lang.String` of `true`, `false`, `1` or `0` (case-insensitive, trimmed).
+    ///
+    /// Returns `Error::ParseFailed` for other strings and `Error::WrongObjectType`
+    /// for other types.
+    pub fn coerce_from(env: &mut Env<'_>, obj: &JObject<'_>) -> Result<bool, Error> {
+        if obj.is_null() {
+            return Err(Error::NullPtr("null object provided for `coerce_from`"));
+        }
+        if is_instance::<JBoolean>(env, obj)? {
+            let obj = env.as_cast::<JBoolean>(obj)?;
+            obj.value(env)
+        } else if is_instance::<JNumber>(env, obj)? {
+            let obj = env.as_cast::<JNumber>(obj)?;
+            let value = match obj.unwrap_number(env) {
+                Ok(NumberValue::Float(v)) => v != 0.,
+                Ok(NumberValue::Double(v)) => v != 0.,
+                Ok(_) | Err(Error::WrongObjectType) => obj.long_value(env)? != 0,
+                Err(e) => return Err(e),
+            };
+            Ok(value)
+        } else if is_instance::<JString>(env, obj)? {
+            let s = env.as_cast::<JString>(obj)?.to_string();
+            match s.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" => Ok(true),
+                "false" | "0" => Ok(false),
+                _ => Err(Error::ParseFailed(s)),
+            }
+        } else {
+            Err(Error::WrongObjectType)
+        }
+    }
+}
+
+/// Value of a Java primitive wrapper object, keeping its original primitive type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberValue {
+    Byte(jbyte),
+    Short(jshort),
+    Int(jint),
+    Long(jlong),
+    Float(jfloat),
+    Double(jdouble),
+}
+
+impl<'local> JNumber<'local> {
+    /// Reads the value of `java.lang.Byte`, `Short`, `Integer`, `Long`, `Float` or `Double`
+    /// according to the concrete class of the object, without converting it to another type.
+    ///
+    /// Returns `Error::WrongObjectType` for other subclasses of `java.lang.Number`
+    /// (like `java.math.BigInteger` or `java.util.concurrent.atomic.AtomicInteger`).
+    pub fn unwrap_number(&self, env: &mut Env<'_>) -> Result<NumberValue, Error> {
+        if self.is_null() {
+            return Err(Error::NullPtr("null `Number` provided for `unwrap_number`"));
+        }
+        let value = if is_instance::<JInteger>(env, self)? {
+            NumberValue::Int(self.int_value(env)?)
+        } else if is_instance::<JLong>(env, self)? {
+            NumberValue::Long(self.long_value(env)?)
+        } else if is_instance::<JDouble>(env, self)? {
+            NumberValue::Double(self.double_value(env)?)
+        } else if is_instance::<JFloat>(env, self)? {
+            NumberValue::Float(self.float_value(env)?)
+        } else if is_instance::<JShort>(env, self)? {
+            NumberValue::Short(self.short_value(env)?)
+        } else if is_instance::<JByte>(env, self)? {
+            NumberValue::Byte(self.byte_value(env)?)
+        } else {
+            return Err(Error::WrongObjectType);
+        };
+        Ok(value)
+    }
+
+    /// Reads the value via `longValue()` and checks if it fits in `byte`.
+    ///
+    /// Unlike the lossy [JNumber::byte_value], which follows Java's narrowing conversion,
+    /// this returns `Error::WrongJValueType` if the value is out of range.
+    pub fn byte_value_checked(&self, env: &mut Env<'_>) -> Result<jbyte, Error> {
+        self.long_value_narrowed(env, "byte")
+    }
+
+    /// Reads the value via `longValue()` and checks if it fits in `short`.
+    ///
+    /// Unlike the lossy [JNumber::short_value], this returns `Error::WrongJValueType`
+    /// if the value is out of range.
+    pub fn short_value_checked(&self, env: &mut Env<'_>) -> Result<jshort, Error> {
+        self.long_value_narrowed(env, "short")
+    }
+
+    /// Reads the value via `longValue()` and checks if it fits in `int`.
+    ///
+    /// Unlike the lossy [JNumber::int_value], this returns `Error::WrongJValueType`
+    /// if the value is out of range.
+    pub fn int_value_checked(&self, env: &mut Env<'_>) -> Result<jint, Error> {
+        self.long_value_narrowed(env, "int")
+    }
+
+    fn long_value_narrowed<T: TryFrom<jlong>>(
+        &self,
+        env: &mut Env<'_>,
+        type_name: &'static str,
+    ) -> Result<T, Error> {
+        T::try_from(self.long_value(env)?).map_err(|_| Error::WrongJValueType(type_name, "long"))
+    }
+}
+
+impl NumberValue {
+    /// Creates a new Java wrapper object of the same primitive type.
+    pub fn new_jnumber<'local>(&self, env: &mut Env<'local>) -> Result<JNumber<'local>, Error> {
+        let number = match *self {
+            Self::Byte(v) => JByte::new(env, v)?.into(),
+            Self::Short(v) => JShort::new(env, v)?.into(),
+            Self::Int(v) => JInteger::new(env, v)?.into(),
+            Self::Long(v) => JLong::new(env, v)?.into(),
+            Self::Float(v) => JFloat::new(env, v)?.into(),
+            Self::Double(v) => JDouble::new(env, v)?.into(),
+        };
+        Ok(number)
+    }
+}
+
+/// Owned content of a `java.lang.StackTraceElement`, read by the cached getters of
+/// `JStackTraceElement`. It is formatted like `StackTraceElement.toString()` in Java 8.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StackFrame {
+    pub class_name: String,
+    pub method_name: String,
+    /// `None` if the file name is unavailable.
+    pub file_name: Option<String>,
+    /// Negative if the line number is unavailable; `-2` indicates a native method.
+    pub line_number: i32,
+}
+
+impl StackFrame {
+    /// Reads the stack trace element; `Error::NullPtr` is returned if it is null.
+    pub fn read(env: &mut Env<'_>, element: &JStackTraceElement<'_>) -> Result<Self, Error> {
+        if element.is_null() {
+            return Err(Error::NullPtr("StackTraceElement"));
+        }
+        let file_name = element.get_file_name(env)?;
+        Ok(Self {
+            class_name: element.get_class_name(env)?.to_string(),
+            method_name: element.get_method_name(env)?.to_string(),
+            file_name: (!file_name.is_null()).then(|| file_name.to_string()),
+            line_number: element.get_line_number(env)?,
+        })
+    }
+
+    /// Returns true if the frame belongs to a native method.
+    pub fn is_native(&self) -> bool {
+        self.line_number == -2
+    }
+}
+
+impl std::fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}(", self.class_name, self.method_name)?;
+        match (&self.file_name, self.line_number) {
+            _ if self.is_native() => write!(f, "Native Method)"),
+            (Some(file), line) if line >= 0 => write!(f, "{file}:{line})"),
+            (Some(file), _) => write!(f, "{file})"),
+            (None, _) => write!(f, "Unknown Source)"),
+        }
+    }
+}
+
+/// Java exception converted into an idiomatic Rust error, keeping the class name, message,
+/// stack frames and the cause chain of the throwable. It is formatted like Java stack traces
+/// without frames, or with frames in the alternate form (`{:#}`).
+///
+/// It holds no JNI reference, so it can be sent to any thread, and it implements
+/// `serde::Serialize` with the `serde` feature for crash reports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct JavaError {
+    pub class_name: String,
+    pub message: Option<String>,
+    /// Stack frames from the innermost one, limited to 256 frames.
+    pub frames: Vec<StackFrame>,
+    pub cause: Option<Box<JavaError>>,
+}
+
+impl JavaError {
+    // Limits the depth of the cause chain, in case of a cycle.
+    const MAX_CAUSE_DEPTH: usize = 32;
+    const MAX_FRAMES: usize = 256;
+
+    /// Reads the throwable and its causes; `Error::NullPtr` is returned if it is null.
+    pub fn from_throwable(env: &mut Env<'_>, throwable: &JThrowable<'_>) -> Result<Self, Error> {
+        if throwable.is_null() {
+            return Err(Error::NullPtr("Throwable"));
+        }
+        let mut chain = Vec::new();
+        // each cause is read in its own local frame, and each stack frame in a nested one;
+        // only the throwables of the chain stay in the outer frame.
+        env.with_local_frame(Self::MAX_CAUSE_DEPTH + 1, |env| -> Result<(), Error> {
+            let mut current = env.new_local_ref(throwable)?;
+            while !current.is_null() && chain.len() < Self::MAX_CAUSE_DEPTH {
+                current =
+                    env.with_local_frame_returning_local::<_, JThrowable, Error>(8, |env| {
+                        let class = env.get_object_class(&current)?;
+                        let class_name = class.get_name(env)?.to_string();
+                        let message = current.get_message(env)?;
+                        let message = (!message.is_null()).then(|| message.to_string());
+                        let elements = current.get_stack_trace(env)?;
+                        let len = elements.len(env)?.min(Self::MAX_FRAMES);
+                        let mut frames = Vec::with_capacity(len);
+                        for i in 0..len {
+                            let frame = env.with_local_frame(4, |env| -> Result<_, Error> {
+                                let element = elements.get_element(env, i)?;
+                                StackFrame::read(env, &element)
+                            })?;
+                            frames.push(frame);
+                        }
+                        chain.push((class_name, message, frames));
+                        let cause = current.get_cause(env)?;
+                        if env.is_same_object(&cause, &current)? {
+                            return Ok(JThrowable::null());
+                        }
+                        Ok(cause)
+                    })?;
+            }
+            Ok(())
+        })?;
+        let mut error = None;
+        for (class_name, message, frames) in chain.into_iter().rev() {
+            error = Some(Self {
+                class_name,
+                message,
+                frames,
+                cause: error.map(Box::new),
+            });
+        }
+        Ok(error.unwrap())
+    }
+
+    /// Converts `Error::CaughtJavaException` returned by `jni` functions, or returns `None`
+    /// for other errors.
+    pub fn from_caught(env: &mut Env<'_>, error: &Error) -> Result<Option<Self>, Error> {
+        let Error::CaughtJavaException { exception, .. } = error else {
+            return Ok(None);
+        };
+        Self::from_throwable(env, exception).map(Some)
+    }
+}
+
+impl std::fmt::Display for JavaError {
+    /// The alternate form (`{:#}`) includes stack frames like `Throwable.printStackTrace()`,
+    /// which is useful for logging on Android, where `ExceptionDescribe` output is unreliable.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.class_name)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        if f.alternate() {
+            for frame in &self.frames {
+                write!(f, "\n\tat {frame}")?;
+            }
+        }
+        if let Some(cause) = &self.cause {
+            if f.alternate() {
+                write!(f, "\nCaused by: {cause:#}")?;
+            } else {
+                write!(f, "\nCaused by: {cause}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for JavaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause.as_deref().map(|e| e as _)
+    }
+}
+
+/// Gets the string form of any object like `String.valueOf(Object)`, matching Java's print
+/// semantics: `"null"` is returned for null, instead of throwing `NullPointerException`.
+pub fn jni_stringify(env: &mut Env<'_>, obj: &JObject<'_>) -> Result<String, Error> {
+    Ok(JObjects::to_string(env, obj)?.to_string())
+}
+
+/// Calls `toString()` on the object (`null` becomes `"null"`), and keeps at most `max_len` UTF-16
+/// units of the result without splitting a surrogate pair, appending `…` if it is truncated.
+/// Only the kept part is copied into Rust, which protects logging paths from huge `toString()`
+/// results of large collections.
+pub fn jni_to_string_truncated(
+    env: &mut Env<'_>,
+    obj: &JObject<'_>,
+    max_len: usize,
+) -> Result<String, Error> {
+    let s = JObjects::to_string(env, obj)?;
+    let seq = s.as_char_sequence();
+    let len = seq.length(env)?;
+    let max_len = jint::try_from(max_len).unwrap_or(jint::MAX);
+    if len <= max_len {
+        return Ok(s.to_string());
+    }
+    let mut end = max_len;
+    if end > 0 && (0xD800..0xDC00).contains(&seq.char_at(env, end - 1)?) {
+        end -= 1;
+    }
+    let part = seq.sub_sequence(env, 0, end)?;
+    let mut truncated = env.as_cast::<JString>(&part)?.to_string();
+    truncated.push('…');
+    Ok(truncated)
+}
+
+/// Resolves a dot-separated path like `config.server.host` from the object, for exploratory
+/// interop and reading configurations. Each segment like `host` is read by the public getter
+/// `getHost()`, `isHost()` or the public field `host`, found by reflection; primitive values
+/// are boxed.
+///
+/// Returns `Error::FieldNotFound` named with the path up to the unresolved segment, or
+/// `Error::NullPtr` if a value before the last segment is null. If a getter throws (or the
+/// field can't be read), the exception is caught and `Error::ParseFailed` is returned with
+/// the path up to the failing segment and the thrown exception (the target exception of
+/// `InvocationTargetException`).
+pub fn jni_get_by_path<'local>(
+    env: &mut Env<'local>,
+    obj: &JObject<'_>,
+    path: &str,
+) -> Result<JObject<'local>, Error> {
+    let mut current = env.new_local_ref(obj)?;
+    for (i, segment) in path.split('.').enumerate() {
+        if current.is_null() {
+            return Err(Error::NullPtr("null object in the middle of the path"));
+        }
+        let sub_path = {
+            let end = path.split('.').take(i + 1).map(str::len).sum::<usize>() + i;
+            &path[..end]
+        };
+        // each segment is read in its own local frame, only the value is kept
+        let value = env.with_local_frame_returning_local::<_, JObject, Error>(8, |env| {
+            get_member_value(env, &current, segment)?.ok_or_else(|| Error::FieldNotFound {
+                name: sub_path.to_string(),
+                sig: "public getter or field".to_string(),
+            })
+        });
+        current = match value {
+            Ok(value) => value,
+            Err(Error::JavaException) => {
+                let Err(Error::CaughtJavaException { exception, .. }) = env.exception_catch()
+                else {
+                    return Err(Error::JavaException);
+                };
+                let mut java_error = JavaError::from_throwable(env, &exception)?;
+                if java_error.class_name == "java.lang.reflect.InvocationTargetException"
+                    && let Some(cause) = java_error.cause.take()
+                {
+                    java_error = *cause;
+                }
+                return Err(Error::ParseFailed(format!(
+                    "failed to read `{sub_path}`: {java_error}"
+                )));
+            }
+            Err(e) => return Err(e),
+        };
+    }
+    Ok(current)
+}
+
+// Reads the value of the getter or the field; returns `None` if neither is found.
+fn get_member_value<'local>(
+    env: &mut Env<'local>,
+    obj: &JObject<'_>,
+    name: &str,
+) -> Result<Option<JObject<'local>>, Error> {
+    let mut capitalized = name.to_string();
+    if let Some(first) = capitalized.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    let class = env.get_object_class(obj)?;
+    let no_params = JObjectArray::<JClass>::new(env, 0, JClass::null())?;
+    for getter in [format!("get{capitalized}"), format!("is{capitalized}")] {
+        let getter = JString::new(env, getter)?;
+        let method = env.call_method(
+            &class,
+            jni::jni_str!("getMethod"),
+            jni::jni_sig!((java.lang.String, java.lang.Class[]) -> java.lang.reflect.Method),
+            &[(&getter).into(), (&no_params).into()],
+        );
+        match method.and_then(|m| m.l()) {
+            Ok(method) => {
+                let method = env.cast_local::<JMethod>(method)?;
+                let no_args = JObjectArray::<JObject>::new(env, 0, JObject::null())?;
+                return method.invoke(env, obj, no_args).map(Some);
+            }
+            Err(Error::JavaException) => env.exception_clear(),
+            Err(e) => return Err(e),
+        }
+    }
+    let field_name = JString::new(env, name)?;
+    let field = env.call_method(
+        &class,
+        jni::jni_str!("getField"),
+        jni::jni_sig!((java.lang.String) -> java.lang.reflect.Field),
+        &[(&field_name).into()],
+    );
+    match field.and_then(|f| f.l()) {
+        Ok(field) => {
+            let field = env.cast_local::<JField>(field)?;
+            field.get(env, obj).map(Some)
+        }
+        Err(Error::JavaException) => {
+            env.exception_clear();
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Reads the Java string into `buf`, which is cleared first; its allocation is reused, which
+/// reduces allocations when reading many strings in a loop. It decodes the modified UTF-8
+/// content like `JString::try_to_string()`. Returns `Error::NullPtr` if the string is null.
+pub fn jni_read_string_into(
+    env: &Env<'_>,
+    string: &JString<'_>,
+    buf: &mut String,
+) -> Result<(), Error> {
+    if string.is_null() {
+        return Err(Error::NullPtr(
+            "null `String` provided for `jni_read_string_into`",
+        ));
+    }
+    let chars = string.mutf8_chars(env)?;
+    buf.clear();
+    buf.push_str(&chars.to_str());
+    Ok(())
+}
+
+/// Creates a `java.lang.String[]` holding the given strings, which is commonly passed to Java
+/// APIs like `Intent.putExtra(String, String[])`. An empty iterator produces a zero-length array
+/// rather than null.
+///
+/// ```
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// jni_with_env(|env| {
+///     let arr = jni_new_string_array(env, ["first", "second"])?;
+///     assert_eq!(arr.len(env)?, 2);
+///     assert_eq!(arr.get_element(env, 0)?.to_string(), "first");
+///     let owned = vec![String::from("x")];
+///     assert_eq!(jni_new_string_array(env, &owned)?.len(env)?, 1);
+///     assert_eq!(jni_new_string_array(env, [""; 0])?.len(env)?, 0);
+///     Ok(())
+/// })
+/// .unwrap();
+/// ```
+pub fn jni_new_string_array<'local, I>(
+    env: &mut Env<'local>,
+    items: I,
+) -> Result<JObjectArray<'local, JString<'local>>, Error>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let items = items.into_iter();
+    let arr = JObjectArray::<JString>::new(env, items.len(), JString::null())?;
+    for (i, item) in items.enumerate() {
+        env.with_local_frame(1, |env| -> Result<(), Error> {
+            let item = JString::new(env, item.as_ref())?;
+            arr.set_element(env, i, item)
+        })?;
+    }
+    Ok(arr)
+}
+
+/// Reads a `java.lang.String[]`, like the one returned by `Intent.getStringArrayExtra()`.
+/// Returns `Error::NullPtr` if the array or any of its elements is null; use
+/// [jni_read_optional_string_array] to accept null elements.
+///
+/// ```
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// jni_with_env(|env| {
+///     let arr = jni_new_string_array(env, ["a", "b"])?;
+///     assert_eq!(jni_read_string_array(env, &arr)?, ["a", "b"]);
+///     Ok(())
+/// })
+/// .unwrap();
+/// ```
+pub fn jni_read_string_array(
+    env: &mut Env<'_>,
+    array: &JObjectArray<'_, JString<'_>>,
+) -> Result<Vec<String>, Error> {
+    jni_read_optional_string_array(env, array)?
+        .into_iter()
+        .map(|item| item.ok_or(Error::NullPtr("null element in `String[]`")))
+        .collect()
+}
+
+/// Reads a `java.lang.String[]` which may contain null elements, mapping them to `None`
+/// instead of empty strings so they stay distinguishable. Returns `Error::NullPtr` if the
+/// array itself is null.
+pub fn jni_read_optional_string_array(
+    env: &mut Env<'_>,
+    array: &JObjectArray<'_, JString<'_>>,
+) -> Result<Vec<Option<String>>, Error> {
+    if array.is_null() {
+        return Err(Error::NullPtr("null `String[]` provided for reading"));
+    }
+    let len = array.len(env)?;
+    let mut result = Vec::with_capacity(len);
+    for i in 0..len {
+        let item = env.with_local_frame(1, |env| -> Result<_, Error> {
+            let item = array.get_element(env, i)?;
+            Ok((!item.is_null()).then(|| item.to_string()))
+        })?;
+        result.push(item);
+    }
+    Ok(result)
+}
+
+/// Creates a Java primitive array (like `int[]` for `jint`) holding a copy of `values`.
+/// An empty slice produces a zero-length array rather than null.
+pub fn jni_new_primitive_array<'local, T: TypeArray>(
+    env: &mut Env<'local>,
+    values: &[T],
+) -> Result<JPrimitiveArray<'local, T>, Error> {
+    let array = JPrimitiveArray::<T>::new(env, values.len())?;
+    array.set_region(env, 0, values)?;
+    Ok(array)
+}
+
+/// Copies the whole Java primitive array into a `Vec`. Returns `Error::NullPtr` if it is null.
+///
+/// To read an untyped object, cast it first, e.g. by `JIntArray::cast_local()`, which returns
+/// `Error::WrongObjectType` if the object is not an `int[]`.
+pub fn jni_read_primitive_array<T: TypeArray + Default>(
+    env: &mut Env<'_>,
+    array: &JPrimitiveArray<'_, T>,
+) -> Result<Vec<T>, Error> {
+    let mut values = vec![T::default(); array.len(env)?];
+    array.get_region(env, 0, &mut values)?;
+    Ok(values)
+}
+
+/// Reads all elements of a `java.util.List` (e.g. the one returned by
+/// `PackageManager.getInstalledApplications()`) as global references, so that a long list
+/// doesn't exhaust the local reference table. Null elements are kept as null references.
+/// Returns `Error::NullPtr` if `list` is null.
+///
+/// The size is recorded before reading: elements appended during the iteration are not read,
+/// and the iteration stops early if `List.get()` throws `IndexOutOfBoundsException` because
+/// the list has shrunk.
+///
+/// Get the `JList` with `env.as_cast::<JList>(&obj)`, which returns `Error::WrongObjectType`
+/// if the object is not a `List`.
+///
+/// ```
+/// use jni::objects::{JObject, JString};
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// jni_with_env(|env| {
+///     let a = JString::new(env, "a")?;
+///     let list = jni_new_array_list(env, [&*a, &JObject::null()])?;
+///     let items = jni_read_list(env, &list)?;
+///     assert_eq!(jni_stringify(env, &items[0])?, "a");
+///     assert!(items[1].is_null());
+///     Ok(())
+/// })
+/// .unwrap();
+/// ```
+pub fn jni_read_list(
+    env: &mut Env<'_>,
+    list: &JList<'_>,
+) -> Result<Vec<Global<JObject<'static>>>, Error> {
+    if list.is_null() {
+        return Err(Error::NullPtr("null `List` provided for `jni_read_list`"));
+    }
+    let len = list.size(env)?.max(0) as usize;
+    let mut items = Vec::with_capacity(len);
+    for i in 0..len {
+        let item = env.with_local_frame(1, |env| -> Result<_, Error> {
+            let item = list.get(env, i as jint)?;
+            env.new_global_ref(item)
+        });
+        match item {
+            Ok(item) => items.push(item),
+            // the list has shrunk during the iteration
+            Err(Error::JavaException) => match env.exception_catch() {
+                Err(Error::CaughtJavaException { name, .. })
+                    if name == "java.lang.IndexOutOfBoundsException"
+                        || name == "java.lang.ArrayIndexOutOfBoundsException" =>
+                {
+                    break;
+                }
+                Err(e) => return Err(e),
+                Ok(()) => return Err(Error::JavaException),
+            },
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(items)
+}
+
+/// Creates a `java.util.ArrayList` holding the given objects in order.
+pub fn jni_new_array_list<'local, I>(
+    env: &mut Env<'local>,
+    items: I,
+) -> Result<JList<'local>, Error>
+where
+    I: IntoIterator,
+    I::Item: AsRef<JObject<'local>>,
+{
+    let items = items.into_iter();
+    let capacity = items.size_hint().0.min(jint::MAX as usize) as jint;
+    let list = JArrayList::new(env, capacity)?;
+    let list = env.cast_local::<JList>(list)?;
+    for item in items {
+        list.add(env, item.as_ref())?;
+    }
+    Ok(list)
+}
+
+// Checks the object against the class cached in the binding API of `T`.
+pub(crate) fn is_instance<T: Reference>(
+    env: &mut Env<'_>,
+    obj: &JObject<'_>,
+) -> Result<bool, Error> {
+    let class = T::lookup_class(env, &LoaderContext::None)?;
+    let class: &JClass = &class;
+    env.is_instance_of(obj, class)
+}
+
 #[test]
 #[cfg(not(target_os = "android"))]
 fn verify_bindings() {
@@ -208,10 +1059,310 @@ fn verify_bindings() {
         JLongAPI::get(env, &ctx).unwrap();
         JFloatAPI::get(env, &ctx).unwrap();
         JDoubleAPI::get(env, &ctx).unwrap();
+        JOptionalAPI::get(env, &ctx).unwrap();
+        JInputStreamAPI::get(env, &ctx).unwrap();
+        JSystemAPI::get(env, &ctx).unwrap();
+        JRuntimeAPI::get(env, &ctx).unwrap();
+        JLocaleAPI::get(env, &ctx).unwrap();
+        JFieldAPI::get(env, &ctx).unwrap();
+        JObjectsAPI::get(env, &ctx).unwrap();
+        JAtomicIntegerAPI::get(env, &ctx).unwrap();
+        JAtomicLongAPI::get(env, &ctx).unwrap();
+        JArrayListAPI::get(env, &ctx).unwrap();
 
         let jinteger = JInteger::new(env, 1)?;
         let _jnum: JNumber = JNumber::cast_local(env, jinteger)?;
 
+        for value in [
+            NumberValue::Byte(-1),
+            NumberValue::Short(i16::MIN),
+            NumberValue::Int(i32::MAX),
+            NumberValue::Long(i64::MIN),
+            NumberValue::Float(0.5),
+            NumberValue::Double(-0.25),
+        ] {
+            let jnum = value.new_jnumber(env)?;
+            assert_eq!(jnum.unwrap_number(env)?, value);
+        }
+
+        let atomic_int = JAtomicInteger::new(env, 5)?;
+        assert_eq!(atomic_int.increment_and_get(env)?, 6);
+        assert!(atomic_int.compare_and_set(env, 6, -7)?);
+        assert!(!atomic_int.compare_and_set(env, 6, 8)?);
+        assert_eq!(atomic_int.get(env)?, -7);
+        let atomic_int: JNumber = atomic_int.into();
+        assert!(matches!(
+            atomic_int.unwrap_number(env),
+            Err(Error::WrongObjectType)
+        ));
+        let atomic_long = JAtomicLong::new(env, i64::MAX - 1)?;
+        assert_eq!(atomic_long.get_and_set(env, i64::MAX)?, i64::MAX - 1);
+        assert_eq!(atomic_long.get(env)?, i64::MAX);
+
+        let jlong: JNumber = JLong::new(env, 300)?.into();
+        assert_eq!(jlong.byte_value(env)?, 44);
+        assert!(jlong.byte_value_checked(env).is_err());
+        assert_eq!(jlong.short_value_checked(env)?, 300);
+        let jlong: JNumber = JLong::new(env, i64::MAX)?.into();
+        assert!(jlong.int_value_checked(env).is_err());
+
+        for (obj, value) in [
+            (JBoolean::new(env, true)?.into(), true),
+            (JInteger::new(env, 0)?.into(), false),
+            (JDouble::new(env, 0.5)?.into(), true),
+            (JString::new(env, " TRUE ")?.into(), true),
+            (JString::new(env, "0")?.into(), false),
+        ] {
+            let obj: JObject = obj;
+            assert_eq!(JBoolean::coerce_from(env, &obj)?, value);
+        }
+        let obj: JObject = JString::new(env, "yes")?.into();
+        assert!(matches!(
+            JBoolean::coerce_from(env, &obj),
+            Err(Error::ParseFailed(_))
+        ));
+
+        let text = JString::new(env, "x")?;
+        let res = env.call_static_method(
+            jni::jni_str!("java/lang/Integer"),
+            jni::jni_str!("parseInt"),
+            jni::jni_sig!((java.lang.String) -> jint),
+            &[(&text).into()],
+        );
+        assert!(matches!(res, Err(Error::JavaException)));
+        let Err(Error::CaughtJavaException { exception, .. }) = env.exception_catch() else {
+            panic!("`NumberFormatException` is not caught");
+        };
+        let java_error = JavaError::from_throwable(env, &exception)?;
+        assert_eq!(java_error.class_name, "java.lang.NumberFormatException");
+        assert_eq!(
+            java_error.to_string(),
+            r#"java.lang.NumberFormatException: For input string: "x""#
+        );
+        assert!(java_error.cause.is_none());
+        assert!(
+            java_error
+                .frames
+                .iter()
+                .any(|f| f.class_name == "java.lang.Integer" && f.method_name == "parseInt")
+        );
+        let msg = JString::new(env, "wrapper")?;
+        let cause = env.new_local_ref(&exception)?;
+        let wrapper = env.new_object(
+            jni::jni_str!("java/lang/RuntimeException"),
+            jni::jni_sig!((java.lang.String, java.lang.Throwable) -> ()),
+            &[(&msg).into(), (&cause).into()],
+        )?;
+        let wrapper = JThrowable::cast_local(env, wrapper)?;
+        let wrapper_error = JavaError::from_throwable(env, &wrapper)?;
+        assert_eq!(wrapper_error.cause.as_deref(), Some(&java_error));
+        assert!(wrapper_error.to_string().starts_with(
+            "java.lang.RuntimeException: wrapper\nCaused by: java.lang.NumberFormatException"
+        ));
+        let full = format!("{wrapper_error:#}");
+        let frame_count = wrapper_error.frames.len() + java_error.frames.len();
+        assert_eq!(full.matches("\n\tat ").count(), frame_count);
+        assert!(full.contains("\nCaused by: java.lang.NumberFormatException: "));
+        assert!(full.contains(&format!("\n\tat {}", java_error.frames[0])));
+        let throwable = env.new_local_ref(&exception)?;
+        let elements = throwable.get_stack_trace(env)?;
+        assert!(elements.len(env)? > 0);
+        for i in 0..elements.len(env)? {
+            let element = elements.get_element(env, i)?;
+            let frame = StackFrame::read(env, &element)?;
+            assert_eq!(frame, java_error.frames[i]);
+            let java_str = element.try_to_string(env)?.to_string();
+            // Java 9+ may prepend the module name
+            assert!(java_str.ends_with(&frame.to_string()));
+        }
+
+        let data: Vec<u8> = (0..20000).map(|i| (i % 251) as u8).collect();
+        let arr = env.byte_array_from_slice(&data)?;
+        let stream = env.new_object(
+            jni::jni_str!("java/io/ByteArrayInputStream"),
+            jni::jni_sig!((jbyte[]) -> ()),
+            &[(&arr).into()],
+        )?;
+        let stream = JInputStream::cast_local(env, stream)?;
+        assert_eq!(stream.read_to_end(env)?, data);
+        assert!(stream.read_to_end(env)?.is_empty());
+        stream.close(env)?;
+
+        let mut buf = String::new();
+        for text in [
+            "a longer string to allocate the buffer",
+            "短い",
+            "emoji 🦀",
+            "",
+        ] {
+            let jtext = JString::new(env, text)?;
+            jni_read_string_into(env, &jtext, &mut buf)?;
+            assert_eq!(buf, text);
+        }
+        assert!(buf.capacity() >= "a longer string to allocate the buffer".len());
+        assert!(matches!(
+            jni_read_string_into(env, &JString::null(), &mut buf),
+            Err(Error::NullPtr(_))
+        ));
+
+        let arr = JObjectArray::<JString>::new(env, 2, JString::null())?;
+        let item = JString::new(env, "a")?;
+        arr.set_element(env, 0, item)?;
+        assert!(matches!(
+            jni_read_string_array(env, &arr),
+            Err(Error::NullPtr(_))
+        ));
+        assert_eq!(
+            jni_read_optional_string_array(env, &arr)?,
+            [Some("a".to_string()), None]
+        );
+        assert!(matches!(
+            jni_read_optional_string_array(env, &JObjectArray::<JString>::null()),
+            Err(Error::NullPtr(_))
+        ));
+        let arr = jni_new_string_array(env, Vec::<String>::new())?;
+        assert!(jni_read_string_array(env, &arr)?.is_empty());
+
+        let list = jni_new_array_list(env, Vec::<JObject>::new())?;
+        assert!(jni_read_list(env, &list)?.is_empty());
+        assert!(matches!(
+            jni_read_list(env, &JList::null()),
+            Err(Error::NullPtr(_))
+        ));
+        // a list which reports 3 elements, but shrinks to 1 element before `get()` is called
+        let shrinking = crate::DynamicProxy::build(
+            env,
+            &LoaderContext::None,
+            &[jni::strings::JNIString::from("java.util.List")],
+            |env, method, args| match method.get_name(env)?.to_string().as_str() {
+                "size" => Ok(JInteger::new(env, 3)?.into()),
+                "get" => {
+                    let index = args.get_element(env, 0)?;
+                    let index = JInteger::cast_local(env, index)?;
+                    if index.value(env)? >= 1 {
+                        env.throw_new(
+                            jni::jni_str!("java/lang/IndexOutOfBoundsException"),
+                            jni::jni_str!("shrunk"),
+                        )?;
+                        return Err(Error::JavaException);
+                    }
+                    Ok(index.into())
+                }
+                _ => Ok(JObject::null()),
+            },
+        )?;
+        let shrinking = env.as_cast::<JList>(&*shrinking)?;
+        assert_eq!(jni_read_list(env, &shrinking)?.len(), 1);
+        let not_list: JObject = JString::new(env, "a")?.into();
+        assert!(matches!(
+            env.as_cast::<JList>(&not_list),
+            Err(Error::WrongObjectType)
+        ));
+
+        let ints = [i32::MIN, -1, 0, 1, i32::MAX];
+        let arr = jni_new_primitive_array(env, &ints)?;
+        assert_eq!(jni_read_primitive_array(env, &arr)?, ints);
+        let longs = [i64::MIN, 0, i64::MAX];
+        let arr: JObject = jni_new_primitive_array(env, &longs)?.into();
+        assert!(matches!(
+            env.as_cast::<jni::objects::JIntArray>(&arr),
+            Err(Error::WrongObjectType)
+        ));
+        let arr = jni::objects::JLongArray::cast_local(env, arr)?;
+        assert_eq!(jni_read_primitive_array(env, &arr)?, longs);
+        fn round_trip<T: TypeArray + Default + PartialEq + std::fmt::Debug>(
+            env: &mut Env<'_>,
+            values: &[T],
+        ) -> Result<(), Error> {
+            let arr = jni_new_primitive_array(env, values)?;
+            assert_eq!(jni_read_primitive_array(env, &arr)?, values);
+            Ok(())
+        }
+        round_trip(env, &[true, false])?;
+        round_trip::<jbyte>(env, &[i8::MIN, -1, i8::MAX])?;
+        round_trip::<jni::sys::jchar>(env, &[0, 0xd83e, 0xffff])?;
+        round_trip::<jshort>(env, &[i16::MIN, i16::MAX])?;
+        round_trip::<jfloat>(env, &[f32::MIN_POSITIVE, -0.5, f32::INFINITY])?;
+        round_trip::<jdouble>(env, &[f64::MAX, 0.25])?;
+        // NaN payloads and the sign of zero are kept, compared by bit patterns
+        let floats = [f32::from_bits(0x7fc0_1234), -0.0, f32::NAN];
+        let arr = jni_new_primitive_array(env, &floats)?;
+        let read: Vec<u32> = jni_read_primitive_array::<jfloat>(env, &arr)?
+            .into_iter()
+            .map(f32::to_bits)
+            .collect();
+        assert_eq!(read, floats.map(f32::to_bits));
+        let doubles = [f64::from_bits(0x7ff8_0000_dead_beef), -0.0, f64::NAN];
+        let arr = jni_new_primitive_array(env, &doubles)?;
+        let read: Vec<u64> = jni_read_primitive_array::<jdouble>(env, &arr)?
+            .into_iter()
+            .map(f64::to_bits)
+            .collect();
+        assert_eq!(read, doubles.map(f64::to_bits));
+        let arr = jni_new_primitive_array::<jint>(env, &[])?;
+        assert!(!arr.is_null());
+        assert!(jni_read_primitive_array(env, &arr)?.is_empty());
+        assert!(matches!(
+            jni_read_primitive_array(env, &jni::objects::JIntArray::null()),
+            Err(Error::NullPtr(_))
+        ));
+
+        let s = JString::new(env, "abc")?;
+        let name = jni_get_by_path(env, &s, "class.name")?;
+        assert_eq!(
+            env.as_cast::<JString>(&name)?.to_string(),
+            "java.lang.String"
+        );
+        let empty = jni_get_by_path(env, &s, "empty")?;
+        assert!(!env.as_cast::<JBoolean>(&empty)?.value(env)?);
+        let jinteger = JInteger::new(env, 3)?;
+        let max = jni_get_by_path(env, &jinteger, "MAX_VALUE")?;
+        assert_eq!(env.as_cast::<JInteger>(&max)?.value(env)?, i32::MAX);
+        let Err(Error::FieldNotFound { name, .. }) = jni_get_by_path(env, &s, "class.nope.x")
+        else {
+            panic!()
+        };
+        assert_eq!(name, "class.nope");
+        // `getISO3Country()` throws `MissingResourceException` for an unknown country
+        let (language, country) = (JString::new(env, "xx")?, JString::new(env, "YY")?);
+        let locale = env.new_object(
+            jni::jni_str!("java/util/Locale"),
+            jni::jni_sig!((java.lang.String, java.lang.String) -> ()),
+            &[(&language).into(), (&country).into()],
+        )?;
+        let Err(Error::ParseFailed(msg)) = jni_get_by_path(env, &locale, "ISO3Country.x") else {
+            panic!()
+        };
+        assert!(msg.starts_with("failed to read `ISO3Country`: "));
+        assert!(msg.contains("java.util.MissingResourceException"));
+        assert!(!env.exception_check());
+
+        assert_eq!(jni_stringify(env, &JObject::null())?, "null");
+        let jboolean = JBoolean::new(env, true)?;
+        assert_eq!(jni_stringify(env, &jboolean)?, "true");
+        let jdouble = JDouble::new(env, 0.5)?;
+        assert_eq!(jni_stringify(env, &jdouble)?, "0.5");
+
+        let s = JString::new(env, "ab😀cd")?;
+        assert_eq!(jni_to_string_truncated(env, &s, 3)?, "ab…");
+        assert_eq!(jni_to_string_truncated(env, &s, 4)?, "ab😀…");
+        assert_eq!(jni_to_string_truncated(env, &s, 6)?, "ab😀cd");
+        assert_eq!(jni_to_string_truncated(env, &JObject::null(), 2)?, "nu…");
+        let jinteger = JInteger::new(env, 12345)?;
+        assert_eq!(jni_to_string_truncated(env, &jinteger, 10)?, "12345");
+
+        let empty = JOptional::empty(env)?;
+        assert!(!empty.is_present(env)?);
+        let empty = JOptional::of_nullable(env, JObject::null())?;
+        assert!(!empty.is_present(env)?);
+        assert!(empty.get(env).is_err());
+        env.exception_clear();
+        let jinteger = JInteger::new(env, 2)?;
+        let present = JOptional::of(env, &jinteger)?;
+        assert!(present.is_present(env)?);
+        let value = present.get(env)?;
+        assert!(env.is_same_object(&value, &jinteger)?);
+
         Ok::<_, jni::errors::Error>(())
     })
     .unwrap();
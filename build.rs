@@ -3,11 +3,40 @@
 // Note: Newer JDK versions (including JDK 21 and above) may not work with Android D8
 // if there are anonymous classes in the Java code, which produces files like `Cls$1.class`
 // (fixed in build tools 35.0.0). Currently `jni-min-helper` doesn't use anonymous classes.
+//
+// Prebuilt artifacts can be supplied to skip the Java compilation: `JNI_MIN_HELPER_DEX` is the
+// path of a dex file for the Android target, and `JNI_MIN_HELPER_CLASSES_DIR` is the directory
+// containing `rust/jniminhelper/InvocHdl.class` for other targets. They are validated strictly.
+//
+// For the Android target, Java sources under the directory `JNI_MIN_HELPER_EXTRA_SRC` and jars
+// listed in `JNI_MIN_HELPER_EXTRA_JARS` (separated like `PATH`) are compiled into the same dex,
+// loaded by `android_helper_class_loader()`. They must not use the `rust.jniminhelper` package.
+//
+// `java/prebuilt.manifest` records digests of the Java sources used for the prebuilt artifacts.
+// When falling back to them, classes with mismatched native method declarations are listed in
+// the generated `helper_manifest.rs`, and loading them fails at runtime with a clear error.
+// Update the manifest with `prebuilt.manifest` in `OUT_DIR` whenever they are rebuilt.
+//
+// `JNI_MIN_HELPER_MIN_API` sets the minimum Android API level of the compiled dex (default 20);
+// it is also assumed for the dex given by `JNI_MIN_HELPER_DEX`, and checked at runtime.
 
-use std::{env, fs, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
 use android_build::{Dexer, JavaBuild};
 
+const ENV_PREBUILT_DEX: &str = "JNI_MIN_HELPER_DEX";
+const ENV_PREBUILT_CLASSES_DIR: &str = "JNI_MIN_HELPER_CLASSES_DIR";
+const ENV_EXTRA_SRC: &str = "JNI_MIN_HELPER_EXTRA_SRC";
+const ENV_EXTRA_JARS: &str = "JNI_MIN_HELPER_EXTRA_JARS";
+const HELPER_PACKAGE: &str = "rust.jniminhelper";
+const ENV_MIN_API: &str = "JNI_MIN_HELPER_MIN_API";
+const DEFAULT_MIN_API: u32 = 20; // also the minimum API level of the prebuilt dex
+const PREBUILT_MANIFEST: &str = "prebuilt.manifest";
+const CLASS_MAGIC: [u8; 4] = [0xCA, 0xFE, 0xBA, 0xBE];
+
 fn main() {
     let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
     let src_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("java");
@@ -21,12 +50,92 @@ fn main() {
         env::set_var("JAVA_TOOL_OPTIONS", "-Duser.language=en");
     }
 
+    println!("cargo:rerun-if-env-changed={ENV_PREBUILT_DEX}");
+    println!("cargo:rerun-if-env-changed={ENV_PREBUILT_CLASSES_DIR}");
+    println!("cargo:rerun-if-env-changed={ENV_EXTRA_SRC}");
+    println!("cargo:rerun-if-env-changed={ENV_EXTRA_JARS}");
+    println!("cargo:rerun-if-env-changed={ENV_MIN_API}");
+    let min_api = env::var(ENV_MIN_API).map_or(DEFAULT_MIN_API, |s| {
+        s.trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid {ENV_MIN_API}: {s}"))
+    });
+    write_helper_manifest(&out_dir, min_api, &[]);
+
     if target_os == "android" {
-        let sources = [
-            src_dir.join("InvocHdl.java"),
-            src_dir.join("BroadcastRec.java"),
-            src_dir.join("PermActivity.java"),
-        ];
+        // `InvocHdl` is always needed by `DynamicProxy`; others are selected by features.
+        let mut class_names = vec!["InvocHdl"];
+        if env::var_os("CARGO_FEATURE_BROADCAST").is_some() {
+            class_names.push("BroadcastRec");
+        }
+        if env::var_os("CARGO_FEATURE_PERMISSION").is_some() {
+            class_names.push("PermActivity");
+        }
+        if env::var_os("CARGO_FEATURE_CONTENT_OBSERVER").is_some() {
+            class_names.push("ContentObs");
+        }
+        if env::var_os("CARGO_FEATURE_ACTIVITY_RESULT").is_some() {
+            class_names.push("ResultActivity");
+        }
+
+        let extra_src_dir = env::var_os(ENV_EXTRA_SRC).map(PathBuf::from);
+        let extra_jars: Vec<PathBuf> = env::var_os(ENV_EXTRA_JARS)
+            .map(|s| env::split_paths(&s).collect())
+            .unwrap_or_default();
+        let has_extra = extra_src_dir.is_some() || !extra_jars.is_empty();
+
+        if let Some(dex_path) = env::var_os(ENV_PREBUILT_DEX) {
+            if has_extra {
+                panic!(
+                    "{ENV_PREBUILT_DEX} cannot be used with {ENV_EXTRA_SRC} or {ENV_EXTRA_JARS}"
+                );
+            }
+            let dex_path = PathBuf::from(dex_path);
+            println!("cargo:rerun-if-changed={}", dex_path.to_string_lossy());
+            let data = fs::read(&dex_path)
+                .unwrap_or_else(|e| panic!("Failed to read {ENV_PREBUILT_DEX}: {e}"));
+            if let Err(s) = validate_dex(&data, &class_names) {
+                panic!("Invalid {ENV_PREBUILT_DEX} ({}): {s}", dex_path.display());
+            }
+            fs::write(out_dir.join("classes.dex"), data).unwrap();
+            return;
+        }
+
+        let mut sources: Vec<_> = class_names
+            .iter()
+            .map(|name| src_dir.join(format!("{name}.java")))
+            .collect();
+        if let Some(dir) = extra_src_dir {
+            println!("cargo:rerun-if-changed={}", dir.to_string_lossy());
+            let mut extra_sources = Vec::new();
+            collect_java_sources(&dir, &mut extra_sources)
+                .unwrap_or_else(|e| panic!("Failed to read {ENV_EXTRA_SRC}: {e}"));
+            for path in extra_sources.iter() {
+                let code = fs::read_to_string(path).unwrap();
+                if declares_helper_package(&code) {
+                    panic!(
+                        "{} declares the reserved package `{HELPER_PACKAGE}`",
+                        path.display()
+                    );
+                }
+            }
+            sources.extend(extra_sources);
+        }
+        for jar in extra_jars.iter() {
+            let data =
+                fs::read(jar).unwrap_or_else(|e| panic!("Failed to read {}: {e}", jar.display()));
+            // entry names are stored as plain strings in the zip directory
+            let reserved = HELPER_PACKAGE.replace('.', "/") + "/";
+            if data
+                .windows(reserved.len())
+                .any(|w| w == reserved.as_bytes())
+            {
+                panic!(
+                    "{} contains classes in the reserved package `{HELPER_PACKAGE}`",
+                    jar.display()
+                );
+            }
+        }
         let android_jar = android_build::android_jar(None);
 
         let out_cls_dir = out_dir.join("classes");
@@ -38,33 +147,70 @@ fn main() {
         let mut err_string = None;
         if android_jar.is_none() {
             err_string.replace("Failed to find android.jar.".to_string());
-        } else if let Err(s) =
-            compile_java_source(sources, [android_jar.clone().unwrap()], out_cls_dir.clone())
-        {
+        } else if let Err(s) = compile_java_source(
+            sources,
+            [android_jar.clone().unwrap()]
+                .into_iter()
+                .chain(extra_jars.iter().cloned()),
+            out_cls_dir.clone(),
+        ) {
             err_string.replace(s);
-        } else if let Err(s) = build_dex_file(out_cls_dir.clone(), android_jar, [], out_dir.clone())
-        {
+        } else if let Err(s) = build_dex_file(
+            out_cls_dir.clone(),
+            android_jar,
+            extra_jars,
+            min_api,
+            out_dir.clone(),
+        ) {
             err_string.replace(s);
         };
 
         if let Some(s) = err_string {
+            if has_extra {
+                panic!("Failed to build the dex with extra Java code:\n{s}");
+            }
             for line in s.lines() {
                 println!("cargo::warning={line}");
             }
+            // The prebuilt dex contains all helper classes, including optional ones (a few KiB).
             println!("cargo::warning=Falling back to the unmanaged prebuilt dex.");
             let prebuilt_dex_path = src_dir.join("classes.dex");
             let out_dex_path = out_dir.join("classes.dex");
             fs::copy(prebuilt_dex_path, out_dex_path)
                 .expect("Failed to access the prebuilt dex file");
+            check_prebuilt(&src_dir, &out_dir, &class_names);
+        } else {
+            generate_prebuilt_manifest(&src_dir, &out_dir);
         }
     } else {
         println!("Building for PC platform...");
+        let out_class_file_dir = out_dir.join("rust").join("jniminhelper");
+
+        if let Some(classes_dir) = env::var_os(ENV_PREBUILT_CLASSES_DIR) {
+            let class_path = PathBuf::from(classes_dir)
+                .join("rust")
+                .join("jniminhelper")
+                .join("InvocHdl.class");
+            println!("cargo:rerun-if-changed={}", class_path.to_string_lossy());
+            let data = fs::read(&class_path).unwrap_or_else(|e| {
+                panic!("Failed to read {}: {e}", class_path.display());
+            });
+            if !data.starts_with(&CLASS_MAGIC) {
+                panic!(
+                    "Invalid {ENV_PREBUILT_CLASSES_DIR}: {} is not a class file",
+                    class_path.display()
+                );
+            }
+            fs::create_dir_all(&out_class_file_dir).unwrap();
+            fs::write(out_class_file_dir.join("InvocHdl.class"), data).unwrap();
+            return;
+        }
+
         if let Err(s) = compile_java_source([src_dir.join("InvocHdl.java")], [], out_dir.clone()) {
             for line in s.lines() {
                 println!("cargo::warning={line}");
             }
             println!("cargo::warning=Falling back to the unmanaged prebuilt class.");
-            let out_class_file_dir = out_dir.join("rust").join("jniminhelper");
             if !out_class_file_dir.try_exists().unwrap() {
                 fs::DirBuilder::new()
                     .recursive(true)
@@ -75,8 +221,131 @@ fn main() {
             let out_class_path = out_class_file_dir.join("InvocHdl.class");
             fs::copy(prebuilt_class_path, out_class_path)
                 .expect("Failed to access the prebuilt class file");
+            check_prebuilt(&src_dir, &out_dir, &["InvocHdl"]);
+        } else {
+            generate_prebuilt_manifest(&src_dir, &out_dir);
+        }
+    }
+}
+
+// Returns the FNV-1a digests of the whole source and its native method declarations.
+fn source_digests(code: &str) -> (u64, u64) {
+    fn fnv1a(data: impl IntoIterator<Item = u8>) -> u64 {
+        data.into_iter().fold(0xcbf29ce484222325, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+    let code = code.replace('\r', "");
+    let native_decls: Vec<String> = code
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("//") && !line.starts_with('*'))
+        .filter(|line| line.split_whitespace().any(|w| w == "native"))
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    (fnv1a(code.bytes()), fnv1a(native_decls.join("\n").bytes()))
+}
+
+// Writes the manifest of current Java sources, to be copied along with rebuilt prebuilt artifacts.
+fn generate_prebuilt_manifest(src_dir: &Path, out_dir: &Path) {
+    let mut manifest = String::from("# <class> <source digest> <native declarations digest>\n");
+    for name in [
+        "InvocHdl",
+        "BroadcastRec",
+        "PermActivity",
+        "ContentObs",
+        "ResultActivity",
+    ] {
+        let path = src_dir.join(format!("{name}.java"));
+        println!("cargo:rerun-if-changed={}", path.to_string_lossy());
+        let (source, native) = source_digests(&fs::read_to_string(path).unwrap());
+        manifest += &format!("{name} {source:016x} {native:016x}\n");
+    }
+    fs::write(out_dir.join(PREBUILT_MANIFEST), manifest).unwrap();
+}
+
+// Compares the current Java sources with the manifest of prebuilt artifacts.
+fn check_prebuilt(src_dir: &Path, out_dir: &Path, class_names: &[&str]) {
+    let manifest_path = src_dir.join(PREBUILT_MANIFEST);
+    println!("cargo:rerun-if-changed={}", manifest_path.to_string_lossy());
+    let manifest = fs::read_to_string(manifest_path).unwrap_or_default();
+    let mut incompatible = Vec::new();
+    for &name in class_names {
+        let path = src_dir.join(format!("{name}.java"));
+        println!("cargo:rerun-if-changed={}", path.to_string_lossy());
+        let (source, native) = source_digests(&fs::read_to_string(path).unwrap());
+        let recorded = manifest.lines().find_map(|line| {
+            let mut fields = line.split_whitespace();
+            (fields.next() == Some(name)).then(|| {
+                let mut digest = || u64::from_str_radix(fields.next().unwrap_or(""), 16).ok();
+                (digest(), digest())
+            })
+        });
+        match recorded {
+            Some((_, Some(n))) if n != native => incompatible.push(name),
+            Some((Some(s), Some(_))) if s == source => (),
+            Some((Some(_), Some(_))) => {
+                println!("cargo::warning=The prebuilt `{name}` is outdated but compatible.")
+            }
+            _ => incompatible.push(name),
+        }
+    }
+    for name in incompatible.iter() {
+        println!("cargo::warning=The prebuilt `{name}` is stale and cannot be used.");
+    }
+    write_helper_manifest(out_dir, DEFAULT_MIN_API, &incompatible);
+}
+
+fn write_helper_manifest(out_dir: &Path, min_api: u32, incompatible: &[&str]) {
+    let list: Vec<String> = incompatible.iter().map(|s| format!("{s:?}")).collect();
+    let code = format!(
+        "// Generated by build.rs: helper classes that are incompatible with the Rust side.\n\
+         const INCOMPATIBLE_HELPER_CLASSES: &[&str] = &[{}];\n\
+         // Generated by build.rs: minimum Android API level of the embedded dex.\n\
+         #[allow(dead_code)]\n\
+         const HELPER_DEX_MIN_API: i32 = {min_api};\n",
+        list.join(", ")
+    );
+    fs::write(out_dir.join("helper_manifest.rs"), code).unwrap();
+}
+
+fn collect_java_sources(dir: &Path, sources: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_java_sources(&path, sources)?;
+        } else if path.extension().is_some_and(|ext| ext == "java") {
+            sources.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn declares_helper_package(code: &str) -> bool {
+    code.lines().any(|line| {
+        line.trim_start()
+            .strip_prefix("package")
+            .map(|s| s.trim().trim_end_matches(';').trim())
+            .is_some_and(|s| s == HELPER_PACKAGE || s.starts_with(&format!("{HELPER_PACKAGE}.")))
+    })
+}
+
+// Checks the dex magic (`dex\n` followed by the version), and looks for type descriptors
+// of the expected classes in the data (which are stored as plain strings in the dex file).
+fn validate_dex(data: &[u8], class_names: &[&str]) -> Result<(), String> {
+    if data.len() < 8 || &data[..4] != b"dex\n" {
+        return Err("bad dex magic".to_string());
+    }
+    for name in class_names {
+        let descriptor = format!("Lrust/jniminhelper/{name};");
+        let found = data
+            .windows(descriptor.len())
+            .any(|w| w == descriptor.as_bytes());
+        if !found {
+            return Err(format!("class `{descriptor}` is not found"));
         }
     }
+    Ok(())
 }
 
 fn compile_java_source(
@@ -119,6 +388,7 @@ fn build_dex_file(
     compiled_classes_path: PathBuf,
     android_jar: Option<PathBuf>,
     jar_dependencies: impl IntoIterator<Item = PathBuf>,
+    min_api: u32,
     output_dir: PathBuf,
 ) -> Result<(), String> {
     let mut dexer = Dexer::new();
@@ -131,7 +401,7 @@ fn build_dex_file(
         dexer.class_path(dependency);
     }
     dexer
-        .android_min_api(20)
+        .android_min_api(min_api)
         .release(env::var("PROFILE").as_ref().map(|s| s.as_str()) == Ok("release"))
         .class_path(&compiled_classes_path)
         .no_desugaring(true)
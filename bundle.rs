@@ -0,0 +1,119 @@
+use crate::{jni_new_string_array, receiver::Intent};
+
+use jni::{Env, errors::Error, objects::JString};
+
+jni::bind_java_type! {
+    pub AndroidBundle => "android.os.Bundle",
+    constructors {
+        fn new(),
+    },
+    methods {
+        fn put_string(key: JString, value: JString),
+        fn put_int(key: JString, value: jint),
+        fn put_long(key: JString, value: jlong),
+        fn put_boolean(key: JString, value: jboolean),
+        fn put_double(key: JString, value: jdouble),
+        fn put_string_array(key: JString, value: JString[]),
+        fn put_bundle(key: JString, value: AndroidBundle),
+    },
+}
+
+/// Typed value stored in [BundleBuilder].
+#[derive(Clone, Debug, PartialEq)]
+pub enum BundleValue {
+    String(String),
+    Int(i32),
+    Long(i64),
+    Bool(bool),
+    Double(f64),
+    StringArray(Vec<String>),
+    Bundle(BundleBuilder),
+}
+
+/// Accumulates typed key-value pairs, which can be converted into an `android.os.Bundle`
+/// or put into an `Intent` as extras. Later values replace earlier ones of the same key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BundleBuilder {
+    entries: Vec<(String, BundleValue)>,
+}
+
+impl BundleBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a value of any supported type.
+    pub fn put(mut self, key: &str, value: BundleValue) -> Self {
+        self.entries.push((key.to_string(), value));
+        self
+    }
+
+    pub fn put_string(self, key: &str, value: &str) -> Self {
+        self.put(key, BundleValue::String(value.to_string()))
+    }
+
+    pub fn put_int(self, key: &str, value: i32) -> Self {
+        self.put(key, BundleValue::Int(value))
+    }
+
+    pub fn put_long(self, key: &str, value: i64) -> Self {
+        self.put(key, BundleValue::Long(value))
+    }
+
+    pub fn put_bool(self, key: &str, value: bool) -> Self {
+        self.put(key, BundleValue::Bool(value))
+    }
+
+    pub fn put_double(self, key: &str, value: f64) -> Self {
+        self.put(key, BundleValue::Double(value))
+    }
+
+    pub fn put_string_array<'a>(self, key: &str, value: impl IntoIterator<Item = &'a str>) -> Self {
+        let value = value.into_iter().map(|s| s.to_string()).collect();
+        self.put(key, BundleValue::StringArray(value))
+    }
+
+    /// Adds a nested bundle.
+    pub fn put_bundle(self, key: &str, value: BundleBuilder) -> Self {
+        self.put(key, BundleValue::Bundle(value))
+    }
+
+    /// Returns true if no value is added.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Creates a new `android.os.Bundle` holding these values.
+    pub fn build<'local>(&self, env: &mut Env<'local>) -> Result<AndroidBundle<'local>, Error> {
+        let bundle = AndroidBundle::new(env)?;
+        for (key, value) in self.entries.iter() {
+            let key = JString::new(env, key)?;
+            match value {
+                BundleValue::String(s) => {
+                    let s = JString::new(env, s)?;
+                    bundle.put_string(env, key, s)?;
+                }
+                BundleValue::Int(i) => bundle.put_int(env, key, *i)?,
+                BundleValue::Long(l) => bundle.put_long(env, key, *l)?,
+                BundleValue::Bool(b) => bundle.put_boolean(env, key, *b)?,
+                BundleValue::Double(d) => bundle.put_double(env, key, *d)?,
+                BundleValue::StringArray(arr) => {
+                    let j_arr = jni_new_string_array(env, arr)?;
+                    bundle.put_string_array(env, key, j_arr)?;
+                }
+                BundleValue::Bundle(builder) => {
+                    let nested = builder.build(env)?;
+                    bundle.put_bundle(env, key, nested)?;
+                }
+            }
+        }
+        Ok(bundle)
+    }
+
+    /// Puts these values into the intent as extras, by `Intent.putExtras(Bundle)`.
+    pub fn apply_to(&self, env: &mut Env<'_>, intent: &Intent<'_>) -> Result<(), Error> {
+        let bundle = self.build(env)?;
+        intent.put_extras(env, bundle)?;
+        Ok(())
+    }
+}
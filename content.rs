@@ -0,0 +1,519 @@
+use crate::{
+    android::get_android_context, bindings::JInputStream, jni_new_string_array,
+    jni_read_string_array, jni_with_env, uri::AndroidUri,
+};
+
+use jni::{
+    Env,
+    errors::Error,
+    objects::{JObjectArray, JString},
+    refs::Global,
+};
+
+const FIELD_TYPE_NULL: i32 = 0;
+const FIELD_TYPE_INTEGER: i32 = 1;
+const FIELD_TYPE_FLOAT: i32 = 2;
+const FIELD_TYPE_BLOB: i32 = 4;
+
+jni::bind_java_type! {
+    pub(crate) ContentResolver => "android.content.ContentResolver",
+    type_map = {
+        AndroidUri => "android.net.Uri",
+        AndroidContentObserver => "android.database.ContentObserver",
+        AndroidCursor => "android.database.Cursor",
+        ParcelFileDescriptor => "android.os.ParcelFileDescriptor",
+        JInputStream => "java.io.InputStream",
+    },
+    methods {
+        fn query(
+            uri: AndroidUri,
+            projection: JString[],
+            selection: JString,
+            selection_args: JString[],
+            sort_order: JString,
+        ) -> AndroidCursor,
+        fn register_content_observer(
+            uri: AndroidUri,
+            notify_for_descendants: jboolean,
+            observer: AndroidContentObserver,
+        ),
+        fn unregister_content_observer(observer: AndroidContentObserver),
+        fn open_file_descriptor(uri: AndroidUri, mode: JString) -> ParcelFileDescriptor,
+        fn open_input_stream(uri: AndroidUri) -> JInputStream,
+    },
+}
+
+jni::bind_java_type! {
+    ParcelFileDescriptor => "android.os.ParcelFileDescriptor",
+    methods {
+        fn detach_fd() -> jint,
+        fn close(),
+    },
+}
+
+jni::bind_java_type! {
+    pub(crate) AndroidContentObserver => "android.database.ContentObserver",
+}
+
+jni::bind_java_type! {
+    pub(crate) AndroidCursor => "android.database.Cursor",
+    methods {
+        fn move_to_next() -> jboolean,
+        fn get_column_count() -> jint,
+        fn get_column_index(column_name: JString) -> jint,
+        fn get_column_names() -> JString[],
+        fn get_type(column_index: jint) -> jint,
+        fn get_long(column_index: jint) -> jlong,
+        fn get_double(column_index: jint) -> jdouble,
+        fn get_string(column_index: jint) -> JString,
+        fn get_blob(column_index: jint) -> jbyte[],
+        fn close(),
+    },
+}
+
+/// Value of a column in the row yielded by [CursorRows], determined by `Cursor.getType()`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CursorValue {
+    Null,
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Blob(Vec<u8>),
+}
+
+/// Queries the content provider of `uri` (like `content://media/external/images/media`) for
+/// columns listed in `projection` (all columns if it is empty). `selection` is the SQL `WHERE`
+/// clause without `WHERE`, in which each `?` is replaced by an item of `args`; `sort` is the
+/// `ORDER BY` clause without `ORDER BY`.
+///
+/// Returns `Error::SecurityViolation` if the provider requires a permission not granted;
+/// returns `Error::NullPtr` if the content provider is not found (a null cursor is returned).
+pub fn content_query(
+    uri: &str,
+    projection: &[&str],
+    selection: Option<&str>,
+    args: &[&str],
+    sort: Option<&str>,
+) -> Result<CursorRows, Error> {
+    jni_with_env(|env| {
+        let uri_obj = AndroidUri::new(env, uri)?;
+        let projection = new_string_array_or_null(env, projection)?;
+        let selection = match selection {
+            Some(s) => JString::new(env, s)?,
+            None => JString::null(),
+        };
+        let args = new_string_array_or_null(env, args)?;
+        let sort = match sort {
+            Some(s) => JString::new(env, s)?,
+            None => JString::null(),
+        };
+        let resolver = get_android_context().get_content_resolver(env)?;
+        let cursor = resolver
+            .query(env, uri_obj, projection, selection, args, sort)
+            .map_err(|e| map_resolver_error(env, e, uri))?;
+        if cursor.is_null() {
+            return Err(Error::NullPtr("ContentResolver.query() returned null"));
+        }
+        let column_count = cursor.get_column_count(env).inspect_err(|_| {
+            let _ = cursor.close(env);
+        })?;
+        Ok(CursorRows {
+            cursor: Some(env.new_global_ref(cursor)?),
+            column_count,
+        })
+    })
+}
+
+/// Opens the content URI (like a document picked through the Storage Access Framework) by
+/// `ContentResolver.openFileDescriptor()` as a Rust `File`. `mode` is like `r`, `w`, `wt` or `rw`.
+///
+/// Returns `Error::SecurityViolation` if access to the URI is not granted, or
+/// `Error::FieldNotFound` if the content doesn't exist.
+pub fn content_open_fd(uri: &str, mode: &str) -> Result<std::fs::File, Error> {
+    use std::os::fd::FromRawFd;
+    let fd = jni_with_env(|env| {
+        let uri_obj = AndroidUri::new(env, uri)?;
+        let mode = JString::new(env, mode)?;
+        let resolver = get_android_context().get_content_resolver(env)?;
+        let pfd = resolver
+            .open_file_descriptor(env, uri_obj, mode)
+            .map_err(|e| map_resolver_error(env, e, uri))?;
+        if pfd.is_null() {
+            return Err(Error::NullPtr(
+                "ContentResolver.openFileDescriptor() returned null",
+            ));
+        }
+        // after detaching, closing the `ParcelFileDescriptor` no longer closes the fd
+        let fd = match pfd.detach_fd(env) {
+            Ok(fd) => fd,
+            Err(Error::JavaException) => {
+                let e = env.exception_catch().err().unwrap_or(Error::JavaException);
+                let _ = pfd.close(env);
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
+        let _ = pfd.close(env);
+        Ok(fd)
+    })?;
+    // Safety: the fd is detached from the `ParcelFileDescriptor`, so it is owned by the `File`.
+    Ok(unsafe { std::fs::File::from_raw_fd(fd) })
+}
+
+/// Reads the whole content of the URI by `ContentResolver.openInputStream()`, which is for small
+/// payloads. Check [content_open_fd] for errors.
+pub fn content_read_bytes(uri: &str) -> Result<Vec<u8>, Error> {
+    jni_with_env(|env| {
+        let uri_obj = AndroidUri::new(env, uri)?;
+        let resolver = get_android_context().get_content_resolver(env)?;
+        let stream = resolver
+            .open_input_stream(env, uri_obj)
+            .map_err(|e| map_resolver_error(env, e, uri))?;
+        if stream.is_null() {
+            return Err(Error::NullPtr(
+                "ContentResolver.openInputStream() returned null",
+            ));
+        }
+        let data = stream.read_to_end(env);
+        let _ = stream.close(env);
+        data
+    })
+}
+
+// Catches the exception thrown by `ContentResolver`, mapping `SecurityException` and
+// `FileNotFoundException` to typed errors.
+fn map_resolver_error(env: &mut Env<'_>, e: Error, uri: &str) -> Error {
+    if !matches!(e, Error::JavaException) {
+        return e;
+    }
+    match env.exception_catch() {
+        Err(Error::CaughtJavaException { name, .. }) if name == "java.lang.SecurityException" => {
+            Error::SecurityViolation
+        }
+        Err(Error::CaughtJavaException { name, .. }) if name == "java.io.FileNotFoundException" => {
+            Error::FieldNotFound {
+                name: uri.to_string(),
+                sig: "content".to_string(),
+            }
+        }
+        Err(e) => e,
+        Ok(()) => Error::JavaException,
+    }
+}
+
+fn new_string_array_or_null<'local>(
+    env: &mut Env<'local>,
+    items: &[&str],
+) -> Result<JObjectArray<'local, JString<'local>>, Error> {
+    if items.is_empty() {
+        return Ok(JObjectArray::<JString>::null());
+    }
+    jni_new_string_array(env, items)
+}
+
+/// Iterator over rows of the cursor returned by [content_query]. The cursor is closed when
+/// the last row is passed, when an error occurs, or on `drop()`.
+#[derive(Debug)]
+pub struct CursorRows {
+    cursor: Option<Global<AndroidCursor<'static>>>,
+    column_count: i32,
+}
+
+impl CursorRows {
+    /// Returns the amount of columns in each row.
+    pub fn column_count(&self) -> usize {
+        self.column_count as usize
+    }
+
+    /// Returns names of columns in the order of values in each row.
+    /// Returns `Error::NullPtr` if the cursor is already closed.
+    pub fn column_names(&self) -> Result<Vec<String>, Error> {
+        let cursor = self.cursor()?;
+        jni_with_env(|env| {
+            let names = cursor.get_column_names(env)?;
+            jni_read_string_array(env, &names)
+        })
+    }
+
+    /// Finds the index of the column in each row. Returns `Error::FieldNotFound` if the column
+    /// doesn't exist, or `Error::NullPtr` if the cursor is already closed.
+    pub fn column_index(&self, name: &str) -> Result<usize, Error> {
+        let cursor = self.cursor()?;
+        let index = jni_with_env(|env| {
+            let column_name = JString::new(env, name)?;
+            cursor.get_column_index(env, column_name)
+        })?;
+        if index < 0 {
+            return Err(Error::FieldNotFound {
+                name: name.to_string(),
+                sig: "cursor column".to_string(),
+            });
+        }
+        Ok(index as usize)
+    }
+
+    fn cursor(&self) -> Result<&Global<AndroidCursor<'static>>, Error> {
+        self.cursor
+            .as_ref()
+            .ok_or(Error::NullPtr("the cursor is closed"))
+    }
+
+    fn close(&mut self) {
+        if let Some(cursor) = self.cursor.take() {
+            let _ = jni_with_env(|env| cursor.close(env));
+        }
+    }
+
+    fn read_row(&self, env: &mut Env<'_>) -> Result<Option<Vec<CursorValue>>, Error> {
+        let cursor = self.cursor()?;
+        if !cursor.move_to_next(env)? {
+            return Ok(None);
+        }
+        let mut row = Vec::with_capacity(self.column_count as usize);
+        for i in 0..self.column_count {
+            let value = match cursor.get_type(env, i)? {
+                FIELD_TYPE_NULL => CursorValue::Null,
+                FIELD_TYPE_INTEGER => CursorValue::Integer(cursor.get_long(env, i)?),
+                FIELD_TYPE_FLOAT => CursorValue::Float(cursor.get_double(env, i)?),
+                FIELD_TYPE_BLOB => {
+                    let blob = cursor.get_blob(env, i)?;
+                    CursorValue::Blob(env.convert_byte_array(blob)?)
+                }
+                _ => CursorValue::String(cursor.get_string(env, i)?.to_string()),
+            };
+            row.push(value);
+        }
+        Ok(Some(row))
+    }
+}
+
+impl Iterator for CursorRows {
+    type Item = Result<Vec<CursorValue>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cursor.as_ref()?;
+        match jni_with_env(|env| self.read_row(env)) {
+            Ok(Some(row)) => Some(Ok(row)),
+            Ok(None) => {
+                self.close();
+                None
+            }
+            Err(e) => {
+                self.close();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl Drop for CursorRows {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(feature = "content-observer")]
+pub use observer::*;
+
+#[cfg(feature = "content-observer")]
+mod observer {
+    use super::*;
+    use crate::{
+        android::{android_helper_class_loader, get_android_context},
+        jni_with_env,
+        proxy::DynamicProxy,
+    };
+    use jni::{
+        Env,
+        errors::Error,
+        objects::{JClass, JObject},
+        refs::{Global, Reference},
+    };
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Condvar, Mutex},
+        task::Waker,
+        time::Duration,
+    };
+
+    jni::bind_java_type! {
+        ContentObs => "rust.jniminhelper.ContentObs",
+        type_map = {
+            ContentObsHdl => "rust.jniminhelper.ContentObs$ContentObsHdl",
+            AndroidContentObserver => "android.database.ContentObserver",
+        },
+        constructors {
+            fn new(hdl: ContentObsHdl),
+        },
+        is_instance_of = {
+            AndroidContentObserver,
+        }
+    }
+
+    jni::bind_java_type! {
+        ContentObsHdl => "rust.jniminhelper.ContentObs$ContentObsHdl",
+    }
+
+    /// Handles `android.database.ContentObserver` object backed by `DynamicProxy`, registered
+    /// to the `ContentResolver` of the current Android context; it is unregistered on `drop()`.
+    #[derive(Debug)]
+    pub struct ContentWatcher {
+        observer: Global<AndroidContentObserver<'static>>,
+        _proxy: DynamicProxy,
+    }
+
+    impl ContentWatcher {
+        /// Registers an observer for changes of the content URI (like `content://settings/system`),
+        /// and descendants of the URI if `notify_descendants` is true.
+        ///
+        /// The closure receives `selfChange` and the changed URI (may be `None` on old devices).
+        /// It is called in a binder thread.
+        pub fn register(
+            uri: &str,
+            notify_descendants: bool,
+            handler: impl Fn(&mut Env, bool, Option<String>) -> Result<(), Error>
+            + Send
+            + Sync
+            + 'static,
+        ) -> Result<Self, Error> {
+            crate::check_helper_class("ContentObs")?;
+            jni_with_env(|env| {
+                let loader = &jni::refs::LoaderContext::Loader(android_helper_class_loader()?);
+                let _ = ContentObsHdlAPI::get(env, loader)?;
+                let _ = ContentObsAPI::get(env, loader)?;
+                let cls_obs_hdl = ContentObsHdl::lookup_class(env, loader)?;
+                use std::ops::Deref;
+                let proxy = DynamicProxy::build(
+                    env,
+                    loader,
+                    [AsRef::<JClass>::as_ref(&cls_obs_hdl.deref())],
+                    move |env, method, args| {
+                        if &method.get_name(env)?.to_string() == "onChange" && args.len(env)? == 2 {
+                            let self_change = args.get_element(env, 0)?;
+                            let self_change = env.as_cast::<crate::JBoolean>(&self_change)?;
+                            let self_change = self_change.value(env)?;
+                            let uri = args.get_element(env, 1)?;
+                            let uri = AndroidUri::string_of(env, &uri)?;
+                            let _ = handler(env, self_change, uri);
+                            env.exception_clear();
+                        }
+                        Ok(JObject::null())
+                    },
+                )?;
+
+                let obs_hdl = env.new_local_ref(proxy.as_ref())?;
+                let obs_hdl = env.cast_local::<ContentObsHdl>(obs_hdl)?;
+                let observer = AndroidContentObserver::from(ContentObs::new(env, obs_hdl)?);
+
+                let uri = AndroidUri::new(env, uri)?;
+                let resolver = get_android_context().get_content_resolver(env)?;
+                resolver.register_content_observer(env, uri, notify_descendants, &observer)?;
+
+                Ok(Self {
+                    observer: env.new_global_ref(observer)?,
+                    _proxy: proxy,
+                })
+            })
+        }
+    }
+
+    impl Drop for ContentWatcher {
+        fn drop(&mut self) {
+            let _ = jni_with_env(|env| {
+                let resolver = get_android_context().get_content_resolver(env)?;
+                resolver.unregister_content_observer(env, &self.observer)
+            });
+        }
+    }
+
+    /// Content change received by [ContentWaiter].
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct ContentChange {
+        pub self_change: bool,
+        pub uri: Option<String>,
+    }
+
+    /// Waits for changes received by the managed `ContentWatcher`.
+    ///
+    /// It implements `futures_core::Stream` with the `futures` feature.
+    #[derive(Debug)]
+    pub struct ContentWaiter {
+        _watcher: ContentWatcher,
+        inner: Arc<ContentWaiterInner>,
+    }
+
+    #[derive(Debug, Default)]
+    struct ContentWaiterInner {
+        changes: Mutex<VecDeque<ContentChange>>,
+        cond: Condvar,
+        waker: Mutex<Option<Waker>>,
+    }
+
+    impl ContentWaiter {
+        /// Creates the waiter with a new content watcher, check [ContentWatcher::register].
+        pub fn build(uri: &str, notify_descendants: bool) -> Result<Self, Error> {
+            let inner = Arc::new(ContentWaiterInner::default());
+            let inner_weak = Arc::downgrade(&inner);
+            let watcher =
+                ContentWatcher::register(uri, notify_descendants, move |_, self_change, uri| {
+                    let Some(inner) = inner_weak.upgrade() else {
+                        return Ok(());
+                    };
+                    let change = ContentChange { self_change, uri };
+                    inner.changes.lock().unwrap().push_back(change);
+                    inner.cond.notify_all();
+                    if let Some(waker) = inner.waker.lock().unwrap().take() {
+                        waker.wake();
+                    }
+                    Ok(())
+                })?;
+            Ok(Self {
+                _watcher: watcher,
+                inner,
+            })
+        }
+
+        /// Returns the amount of received changes available for checking.
+        pub fn count_received(&self) -> usize {
+            self.inner.changes.lock().unwrap().len()
+        }
+
+        /// Takes the next received change if available.
+        pub fn take_next(&self) -> Option<ContentChange> {
+            self.inner.changes.lock().unwrap().pop_front()
+        }
+
+        /// Waits for receiving a change.
+        pub fn wait_timeout(&self, timeout: Duration) -> Option<ContentChange> {
+            let changes = self.inner.changes.lock().unwrap();
+            let (mut changes, _) = self
+                .inner
+                .cond
+                .wait_timeout_while(changes, timeout, |q| q.is_empty())
+                .unwrap();
+            changes.pop_front()
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    impl futures_core::Stream for ContentWaiter {
+        type Item = ContentChange;
+
+        fn poll_next(
+            self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Option<Self::Item>> {
+            // the waker is registered with the queue locked, so no change can be missed
+            let mut changes = self.inner.changes.lock().unwrap();
+            if let Some(change) = changes.pop_front() {
+                return std::task::Poll::Ready(Some(change));
+            }
+            self.inner.waker.lock().unwrap().replace(cx.waker().clone());
+            std::task::Poll::Pending
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.count_received(), None)
+        }
+    }
+}
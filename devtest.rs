@@ -0,0 +1,148 @@
+use std::panic;
+
+use crate::{android::get_android_context, android_toast, panic_message};
+
+use jni::{errors::Error, objects::JString};
+
+/// A device test case: its name and the function returning `Err` with a message on failure.
+pub type DeviceTest = (&'static str, fn() -> Result<(), String>);
+
+/// Outcome of a case executed by [run_device_tests].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceTestResult {
+    pub name: &'static str,
+    /// `Err` holds the returned message, or the panic message prefixed by `panicked: `.
+    pub outcome: Result<(), String>,
+}
+
+impl DeviceTestResult {
+    pub fn passed(&self) -> bool {
+        self.outcome.is_ok()
+    }
+}
+
+/// Smoke tests of this crate's Android-only features: `android_toast`, the embedded dex,
+/// `PrefsWatcher`, `PermissionRequest` (with the `permission` feature) and `BroadcastWaiter`
+/// (with the `broadcast` feature).
+/// They can be passed to [run_device_tests] along with the caller's own cases.
+pub const BUILTIN_DEVICE_TESTS: &[DeviceTest] = &[
+    // it goes first, to check that the toast works before any other helper reads the context
+    ("jni_min_helper::toast", test_toast),
+    ("jni_min_helper::dex", test_dex),
+    ("jni_min_helper::prefs", test_prefs_watcher),
+    #[cfg(feature = "permission")]
+    ("jni_min_helper::permission", test_permission),
+    #[cfg(feature = "broadcast")]
+    ("jni_min_helper::broadcast", test_broadcast),
+];
+
+/// Runs `tests` one by one in a new worker thread, then returns their results in order.
+/// Panics of the cases are caught and counted as failures. This is meant to be called from
+/// `android_main()` of a test application; the calling thread is blocked until all cases
+/// are finished, which must not be the main (UI) thread if any case depends on it.
+///
+/// Each result is logged by `log::info!` in a line that can be picked out of logcat:
+/// `DEVICE-TEST PASS <name>` or `DEVICE-TEST FAIL <name>: <message>`, followed by
+/// `DEVICE-TEST SUMMARY <passed>/<total>` at last. The summary is also shown as a toast
+/// if `show_toast` is true.
+///
+/// If no logger is installed, [init_android_logger](crate::init_android_logger) is called with
+/// the tag `jni-min-helper` and the `Info` level; a logger installed by the application is kept,
+/// and it must pass `Info` messages for these lines to appear.
+///
+/// ```ignore
+/// fn my_case() -> Result<(), String> {
+///     let bytes = jni_min_helper::android_asset_bytes("data.bin").map_err(|e| e.to_string())?;
+///     (!bytes.is_empty()).then_some(()).ok_or("empty asset".into())
+/// }
+///
+/// let mut tests = jni_min_helper::BUILTIN_DEVICE_TESTS.to_vec();
+/// tests.push(("my_case", my_case));
+/// let results = jni_min_helper::run_device_tests(&tests, true);
+/// ```
+pub fn run_device_tests(tests: &[DeviceTest], show_toast: bool) -> Vec<DeviceTestResult> {
+    if log::max_level() == log::LevelFilter::Off {
+        let _ = crate::init_android_logger("jni-min-helper", log::LevelFilter::Info);
+    }
+    let tests = tests.to_vec();
+    let worker = std::thread::Builder::new()
+        .name("device-test".to_string())
+        .spawn(move || {
+            tests
+                .into_iter()
+                .map(|(name, test)| {
+                    let outcome = panic::catch_unwind(test)
+                        .unwrap_or_else(|e| Err(format!("panicked: {}", panic_message(&*e))));
+                    match &outcome {
+                        Ok(()) => log::info!("DEVICE-TEST PASS {name}"),
+                        Err(msg) => log::info!("DEVICE-TEST FAIL {name}: {msg}"),
+                    }
+                    DeviceTestResult { name, outcome }
+                })
+                .collect::<Vec<_>>()
+        })
+        .expect("failed to spawn the device test thread");
+    let results = worker.join().unwrap_or_default();
+
+    let passed = results.iter().filter(|r| r.passed()).count();
+    let summary = format!("DEVICE-TEST SUMMARY {passed}/{}", results.len());
+    log::info!("{summary}");
+    if show_toast {
+        let _ = android_toast(&summary, true);
+    }
+    results
+}
+
+fn test_toast() -> Result<(), String> {
+    android_toast("jni-min-helper device test", false).map_err(|e| e.to_string())
+}
+
+fn test_dex() -> Result<(), String> {
+    crate::android_helper_class_loader()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn test_prefs_watcher() -> Result<(), String> {
+    const PREFS_NAME: &str = "jni_min_helper_device_test";
+    const KEY: &str = "last_run";
+    let watcher = crate::PrefsWatcher::watch(PREFS_NAME).map_err(|e| e.to_string())?;
+    // the listener is only called if the value is changed
+    let value = format!("{:?}", std::time::SystemTime::now());
+    crate::jni_with_env(|env| {
+        let name = JString::new(env, PREFS_NAME)?;
+        let prefs = get_android_context().get_shared_preferences(env, name, 0)?;
+        let editor = prefs.edit(env)?;
+        let (key, value) = (JString::new(env, KEY)?, JString::new(env, &value)?);
+        editor.put_string(env, key, value)?;
+        if !editor.commit(env)? {
+            return Err(Error::NullPtr("SharedPreferences.Editor.commit() failed"));
+        }
+        Ok(())
+    })
+    .map_err(|e| e.to_string())?;
+    match watcher.wait_timeout(std::time::Duration::from_secs(5)) {
+        Some(Some(key)) if key == KEY => Ok(()),
+        other => Err(format!("expected the changed key `{KEY}`, got {other:?}")),
+    }
+}
+
+#[cfg(feature = "permission")]
+fn test_permission() -> Result<(), String> {
+    // normal permissions are granted at installation, but the manifest may not declare it;
+    // this only checks that the query itself works.
+    crate::PermissionRequest::has_permission("android.permission.INTERNET")
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "broadcast")]
+fn test_broadcast() -> Result<(), String> {
+    // the sticky battery status is delivered right after the registration.
+    let waiter = crate::BroadcastWaiter::build(["android.intent.action.BATTERY_CHANGED"])
+        .map_err(|e| e.to_string())?;
+    waiter
+        .wait_timeout(std::time::Duration::from_secs(5))
+        .map(|_| ())
+        .ok_or_else(|| "no sticky BATTERY_CHANGED intent received".to_string())
+}
@@ -0,0 +1,29 @@
+package rust.jniminhelper;
+
+import android.database.ContentObserver;
+import android.net.Uri;
+
+public class ContentObs extends ContentObserver {
+    public interface ContentObsHdl {
+        public void onChange(boolean selfChange, Uri uri);
+    }
+
+    ContentObsHdl hdl;
+    public ContentObs(ContentObsHdl hdl) {
+        // without a handler, `onChange()` is called in a binder thread
+        super(null);
+        this.hdl = hdl;
+    }
+
+    @Override
+    public void onChange(boolean selfChange) {
+        this.onChange(selfChange, null);
+    }
+
+    @Override
+    public void onChange(boolean selfChange, Uri uri) {
+        if (this.hdl != null) {
+            this.hdl.onChange(selfChange, uri);
+        }
+    }
+}
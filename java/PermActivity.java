@@ -7,6 +7,8 @@ import android.content.Intent;
 public class PermActivity extends Activity {
     static final String EXTRA_PERM_ARRAY = "rust.jniminhelper.perm_array";
     static final String EXTRA_TITLE = "rust.jniminhelper.perm_activity_title";
+    static final String EXTRA_GRANT_RESULTS = "rust.jniminhelper.grant_results";
+    static final String ACTION_PERM_RESULT = "rust.jniminhelper.PERM_RESULT";
     
     // to be registered in native code
     private native void nativeOnRequestPermissionsResult(String[] permissions, int[] grantResults);
@@ -31,7 +33,17 @@ public class PermActivity extends Activity {
     public void onRequestPermissionsResult(int requestCode,
         String[] permissions, int[] grantResults)
     {
-        this.nativeOnRequestPermissionsResult(permissions, grantResults);
+        try {
+            this.nativeOnRequestPermissionsResult(permissions, grantResults);
+        } catch (Throwable e) {
+            // the result is still delivered by the broadcast below
+        }
+        // fallback delivery path, ignored by the native side if the result is already received
+        Intent result = new Intent(ACTION_PERM_RESULT);
+        result.setPackage(this.getPackageName());
+        result.putExtra(EXTRA_PERM_ARRAY, permissions);
+        result.putExtra(EXTRA_GRANT_RESULTS, grantResults);
+        this.sendBroadcast(result);
         this.finish();
     }
 }
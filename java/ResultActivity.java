@@ -0,0 +1,59 @@
+package rust.jniminhelper;
+
+import android.app.Activity;
+import android.os.Bundle;
+import android.content.Intent;
+
+public class ResultActivity extends Activity {
+    static final String EXTRA_TARGET_INTENT = "rust.jniminhelper.target_intent";
+    static final String EXTRA_TOKEN = "rust.jniminhelper.result_token";
+
+    // to be registered in native code
+    private native void nativeOnActivityResult(long token, int resultCode, Intent data);
+    private native void nativeOnCancelled(long token);
+
+    private boolean delivered = false;
+
+    @Override
+    protected void onCreate(Bundle savedInstanceState) {
+        super.onCreate(savedInstanceState);
+        if (savedInstanceState != null) {
+            // recreated after a configuration change, the target activity is already started
+            return;
+        }
+        Intent target = this.getIntent().getParcelableExtra(EXTRA_TARGET_INTENT);
+        try {
+            this.startActivityForResult(target, 0);
+        } catch (Throwable e) {
+            this.finish();
+        }
+    }
+
+    @Override
+    protected void onActivityResult(int requestCode, int resultCode, Intent data) {
+        if (!this.delivered) {
+            this.delivered = true;
+            try {
+                this.nativeOnActivityResult(this.token(), resultCode, data);
+            } catch (Throwable e) {
+            }
+        }
+        this.finish();
+    }
+
+    @Override
+    protected void onDestroy() {
+        if (!this.delivered && this.isFinishing()) {
+            this.delivered = true;
+            try {
+                this.nativeOnCancelled(this.token());
+            } catch (Throwable e) {
+            }
+        }
+        super.onDestroy();
+    }
+
+    private long token() {
+        return this.getIntent().getLongExtra(EXTRA_TOKEN, 0);
+    }
+}
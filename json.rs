@@ -0,0 +1,292 @@
+//! Bindings of `org.json` classes, which are available in the Android framework.
+//! On desktop platforms, a `org.json` jar (like `json-20240303.jar`) must be put in
+//! the class path of the JVM; unit tests of this module read the `CLASSPATH` variable.
+
+jni::bind_java_type! {
+    pub JsonObject => "org.json.JSONObject",
+    type_map = {
+        JsonArray => "org.json.JSONArray",
+    },
+    constructors {
+        fn new(),
+        fn new_from_string(source: JString),
+    },
+    fields {
+        /// Gets `JSONObject.NULL`, which represents `null` values in `JSONObject` and `JSONArray`.
+        #[allow(non_snake_case)]
+        static NULL {
+            sig = JObject,
+            get = NULL,
+        },
+    },
+    methods {
+        fn keys() -> JIterator,
+        fn length() -> jint,
+        fn opt(name: JString) -> JObject,
+        fn put_bool {
+            name = "put",
+            sig = (name: JString, value: jboolean) -> JsonObject,
+        },
+        fn put_long {
+            name = "put",
+            sig = (name: JString, value: jlong) -> JsonObject,
+        },
+        fn put_double {
+            name = "put",
+            sig = (name: JString, value: jdouble) -> JsonObject,
+        },
+        /// Puts a `String`, `JSONObject`, `JSONArray`, `JSONObject.NULL` or a wrapper object.
+        fn put_object {
+            name = "put",
+            sig = (name: JString, value: JObject) -> JsonObject,
+        },
+        fn to_string() -> JString,
+    },
+}
+
+jni::bind_java_type! {
+    pub JsonArray => "org.json.JSONArray",
+    constructors {
+        fn new(),
+        fn new_from_string(source: JString),
+    },
+    methods {
+        fn length() -> jint,
+        fn opt(index: jint) -> JObject,
+        fn put_bool {
+            name = "put",
+            sig = (value: jboolean) -> JsonArray,
+        },
+        fn put_long {
+            name = "put",
+            sig = (value: jlong) -> JsonArray,
+        },
+        fn put_double {
+            name = "put",
+            sig = (value: jdouble) -> JsonArray,
+        },
+        /// Puts a `String`, `JSONObject`, `JSONArray`, `JSONObject.NULL` or a wrapper object.
+        fn put_object {
+            name = "put",
+            sig = (value: JObject) -> JsonArray,
+        },
+        fn to_string() -> JString,
+    },
+}
+
+/// Converts the JSON value into a `JSONObject`, `JSONArray`, `String`, `Boolean`, `Long`,
+/// `Double` or `JSONObject.NULL` recursively, which can be passed to Java APIs expecting
+/// `org.json` objects. Unsigned integers beyond `i64::MAX` are converted to `Double`.
+#[cfg(feature = "serde-json")]
+pub fn jni_new_json<'local>(
+    env: &mut jni::Env<'local>,
+    value: &serde_json::Value,
+) -> Result<jni::objects::JObject<'local>, jni::errors::Error> {
+    use crate::{JBoolean, JDouble, JLong};
+    use jni::objects::{JObject, JString};
+    use serde_json::Value;
+    let obj: JObject = match value {
+        Value::Null => JsonObject::NULL(env)?,
+        Value::Bool(b) => JBoolean::new(env, *b)?.into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(v) => JLong::new(env, v)?.into(),
+            None => JDouble::new(env, n.as_f64().unwrap_or(f64::NAN))?.into(),
+        },
+        Value::String(s) => JString::new(env, s)?.into(),
+        Value::Array(values) => {
+            let arr = JsonArray::new(env)?;
+            for value in values {
+                env.with_local_frame(4, |env| -> Result<(), jni::errors::Error> {
+                    let value = jni_new_json(env, value)?;
+                    arr.put_object(env, value)?;
+                    Ok(())
+                })?;
+            }
+            arr.into()
+        }
+        Value::Object(map) => {
+            let obj = JsonObject::new(env)?;
+            for (key, value) in map {
+                env.with_local_frame(4, |env| -> Result<(), jni::errors::Error> {
+                    let key = JString::new(env, key)?;
+                    let value = jni_new_json(env, value)?;
+                    obj.put_object(env, key, value)?;
+                    Ok(())
+                })?;
+            }
+            obj.into()
+        }
+    };
+    Ok(obj)
+}
+
+/// Reads the `org.json` object, `String`, `Boolean` or `Number` recursively into a JSON value;
+/// `null` and `JSONObject.NULL` are read as `Value::Null`. Numbers other than primitive
+/// wrappers (like `BigDecimal`) are parsed from their string forms.
+///
+/// Returns `Error::WrongObjectType` for other types, and `Error::ParseFailed` for numbers
+/// not representable in JSON (like `NaN`).
+#[cfg(feature = "serde-json")]
+pub fn jni_get_json(
+    env: &mut jni::Env<'_>,
+    obj: &jni::objects::JObject<'_>,
+) -> Result<serde_json::Value, jni::errors::Error> {
+    use crate::{JBoolean, JNumber, NumberValue, bindings::is_instance};
+    use jni::{errors::Error, objects::JString};
+    use serde_json::{Number, Value};
+    if obj.is_null() {
+        return Ok(Value::Null);
+    }
+    let null = JsonObject::NULL(env)?;
+    if env.is_same_object(obj, &null)? {
+        return Ok(Value::Null);
+    }
+    let value = if is_instance::<JsonObject>(env, obj)? {
+        let json_obj = env.as_cast::<JsonObject>(obj)?;
+        let keys = json_obj.keys(env)?;
+        let mut map = serde_json::Map::new();
+        while keys.has_next(env)? {
+            env.with_local_frame(4, |env| -> Result<(), Error> {
+                let Some(key) = keys.next(env)? else {
+                    return Ok(());
+                };
+                let key = env.as_cast::<JString>(&key)?;
+                let value = json_obj.opt(env, &*key)?;
+                map.insert(key.to_string(), jni_get_json(env, &value)?);
+                Ok(())
+            })?;
+        }
+        Value::Object(map)
+    } else if is_instance::<JsonArray>(env, obj)? {
+        let arr = env.as_cast::<JsonArray>(obj)?;
+        let mut values = Vec::new();
+        for i in 0..arr.length(env)? {
+            let value = env.with_local_frame(4, |env| -> Result<Value, Error> {
+                let value = arr.opt(env, i)?;
+                jni_get_json(env, &value)
+            })?;
+            values.push(value);
+        }
+        Value::Array(values)
+    } else if is_instance::<JString>(env, obj)? {
+        Value::String(env.as_cast::<JString>(obj)?.to_string())
+    } else if is_instance::<JBoolean>(env, obj)? {
+        Value::Bool(env.as_cast::<JBoolean>(obj)?.value(env)?)
+    } else if is_instance::<JNumber>(env, obj)? {
+        let number = env.as_cast::<JNumber>(obj)?;
+        let number = match number.unwrap_number(env) {
+            Ok(NumberValue::Byte(v)) => Number::from(v),
+            Ok(NumberValue::Short(v)) => Number::from(v),
+            Ok(NumberValue::Int(v)) => Number::from(v),
+            Ok(NumberValue::Long(v)) => Number::from(v),
+            Ok(NumberValue::Float(v)) => {
+                Number::from_f64(v as f64).ok_or(Error::ParseFailed(v.to_string()))?
+            }
+            Ok(NumberValue::Double(v)) => {
+                Number::from_f64(v).ok_or(Error::ParseFailed(v.to_string()))?
+            }
+            Err(Error::WrongObjectType) => {
+                let s = crate::jni_stringify(env, obj)?;
+                s.parse().map_err(|_| Error::ParseFailed(s))?
+            }
+            Err(e) => return Err(e),
+        };
+        Value::Number(number)
+    } else {
+        return Err(Error::WrongObjectType);
+    };
+    Ok(value)
+}
+
+#[test]
+#[cfg(not(target_os = "android"))]
+fn verify_json_bindings() {
+    use crate::{jni_init_vm_for_unit_test, jni_with_env};
+    use jni::{jni_str, objects::JString};
+    jni_init_vm_for_unit_test();
+    jni_with_env(|env| {
+        let ctx = jni::refs::LoaderContext::None;
+        if ctx
+            .load_class(env, jni_str!("org.json.JSONObject"), false)
+            .is_err()
+        {
+            env.exception_clear();
+            eprintln!("`org.json` is not in the class path, skipping the test.");
+            return Ok(());
+        }
+        JsonObjectAPI::get(env, &ctx).unwrap();
+        JsonArrayAPI::get(env, &ctx).unwrap();
+
+        let obj = JsonObject::new(env)?;
+        let key = JString::new(env, "a")?;
+        obj.put_long(env, &key, 1)?;
+        let arr = JsonArray::new(env)?;
+        arr.put_bool(env, true)?;
+        let null = JsonObject::NULL(env)?;
+        arr.put_object(env, null)?;
+        let key_arr = JString::new(env, "b")?;
+        obj.put_object(env, &key_arr, &arr)?;
+        assert_eq!(obj.length(env)?, 2);
+
+        let s = obj.to_string(env)?;
+        let parsed = JsonObject::new_from_string(env, s)?;
+        let keys = parsed.keys(env)?;
+        let mut count = 0;
+        while keys.has_next(env)? {
+            let _ = keys.next(env)?;
+            count += 1;
+        }
+        assert_eq!(count, 2);
+        let arr = parsed.opt(env, key_arr)?;
+        let arr = JsonArray::cast_local(env, arr)?;
+        assert_eq!(arr.length(env)?, 2);
+        Ok::<_, jni::errors::Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+#[cfg(all(not(target_os = "android"), feature = "serde-json"))]
+fn verify_json_conversion() {
+    use crate::{jni_init_vm_for_unit_test, jni_with_env};
+    use jni::{jni_str, objects::JString};
+    jni_init_vm_for_unit_test();
+    jni_with_env(|env| {
+        let ctx = jni::refs::LoaderContext::None;
+        if ctx
+            .load_class(env, jni_str!("org.json.JSONObject"), false)
+            .is_err()
+        {
+            env.exception_clear();
+            eprintln!("`org.json` is not in the class path, skipping the test.");
+            return Ok(());
+        }
+        let value = serde_json::json!({
+            "name": "jni",
+            "ok": true,
+            "count": -3,
+            "ratio": 0.5,
+            "none": null,
+            "nested": { "list": [1, "two", [false, null], { "x": 1.25 }] },
+        });
+        let obj = jni_new_json(env, &value)?;
+        assert!(env.is_instance_of(&obj, jni_str!("org/json/JSONObject"))?);
+        assert_eq!(jni_get_json(env, &obj)?, value);
+
+        let big = serde_json::json!(u64::MAX);
+        let obj = jni_new_json(env, &big)?;
+        assert_eq!(jni_get_json(env, &obj)?, serde_json::json!(u64::MAX as f64));
+
+        let s = JString::new(env, "text")?;
+        assert_eq!(jni_get_json(env, &s)?, serde_json::json!("text"));
+        let null = jni::objects::JObject::null();
+        assert_eq!(jni_get_json(env, &null)?, serde_json::Value::Null);
+        let thread = jni::objects::JThread::current_thread(env)?;
+        assert!(matches!(
+            jni_get_json(env, &thread),
+            Err(jni::errors::Error::WrongObjectType)
+        ));
+        Ok::<_, jni::errors::Error>(())
+    })
+    .unwrap();
+}
@@ -11,36 +11,471 @@
 pub use bindings::*;
 pub use proxy::*;
 
+#[cfg(feature = "json")]
+pub use json::*;
+
 #[cfg(target_os = "android")]
-pub use {android::*, permission::*, receiver::*};
+pub use {
+    android::*, bundle::*, content::*, lifecycle::*, looper::*, power::*, prefs::*, receiver::*,
+    service::*, ui::*, uri::*,
+};
+
+#[cfg(all(target_os = "android", feature = "permission"))]
+pub use permission::*;
+
+#[cfg(all(target_os = "android", feature = "activity-result"))]
+pub use activity::*;
+
+#[cfg(all(target_os = "android", feature = "android-log"))]
+pub use logger::init_android_logger;
+
+#[cfg(all(target_os = "android", feature = "device-test"))]
+pub use devtest::*;
 
 #[cfg(not(target_os = "android"))]
 macro_rules! warn {
     ($($arg:tt)+) => (eprintln!($($arg)+))
 }
 
-#[cfg(target_os = "android")]
+#[cfg(all(target_os = "android", not(feature = "android-log")))]
 macro_rules! warn {
     ($($arg:tt)+) => (log::warn!($($arg)+))
 }
 
+#[cfg(all(target_os = "android", feature = "android-log"))]
+macro_rules! warn {
+    ($($arg:tt)+) => ({
+        crate::logger::check_logger();
+        log::warn!($($arg)+)
+    })
+}
+
 mod bindings;
 mod proxy;
 
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(all(target_os = "android", feature = "activity-result"))]
+mod activity;
 #[cfg(target_os = "android")]
 mod android;
 #[cfg(target_os = "android")]
+mod bundle;
+#[cfg(target_os = "android")]
+mod content;
+#[cfg(all(target_os = "android", feature = "device-test"))]
+mod devtest;
+#[cfg(target_os = "android")]
+mod lifecycle;
+#[cfg(all(target_os = "android", feature = "android-log"))]
+mod logger;
+#[cfg(target_os = "android")]
+mod looper;
+#[cfg(all(target_os = "android", feature = "permission"))]
 mod permission;
 #[cfg(target_os = "android")]
+mod power;
+#[cfg(target_os = "android")]
+mod prefs;
+#[cfg(target_os = "android")]
 mod receiver;
+#[cfg(target_os = "android")]
+mod service;
+#[cfg(target_os = "android")]
+mod ui;
+#[cfg(target_os = "android")]
+mod uri;
 
 use jni::{Env, JavaVM, errors::Error};
 
+include!(concat!(env!("OUT_DIR"), "/helper_manifest.rs"));
+
+// Fails with a descriptive error instead of a `NoSuchMethodError` deep inside the JVM, if the
+// build script has fallen back to a prebuilt helper class that doesn't match the Rust side.
+pub(crate) fn check_helper_class(name: &str) -> Result<(), Error> {
+    if INCOMPATIBLE_HELPER_CLASSES.contains(&name) {
+        let msg = format!(
+            "helper class `rust.jniminhelper.{name}` is stale, \
+             rebuild with JDK >= 8 (and android.jar) or update the prebuilt"
+        );
+        warn!("{msg}");
+        return Err(Error::NoSuchMethod(msg));
+    }
+    Ok(())
+}
+
+// Checks the dex header before handing the data to the class loader: the magic is `dex\n`
+// followed by a 3-digit format version and `\0`, and the header is 0x70 bytes long.
+#[cfg(any(test, target_os = "android"))]
+pub(crate) fn dex_header_is_valid(dex_data: &[u8]) -> bool {
+    dex_data.len() >= 0x70
+        && dex_data.starts_with(b"dex\n")
+        && dex_data[4..7].iter().all(u8::is_ascii_digit)
+        && dex_data[7] == 0
+}
+
+// Registration status of `BroadcastReceiver::register_while_resumed`. Lifecycle events are
+// delivered in the main thread, while the registration may be cancelled in another thread;
+// the caller holds a lock of the status while doing the returned action, then calls `done`
+// if it succeeds. It is kept apart from JNI calls for testing lifecycle sequences on the host.
+#[cfg(any(test, all(target_os = "android", feature = "broadcast")))]
+#[derive(Debug, Default)]
+pub(crate) struct BoundStatus {
+    pub(crate) registered: bool,
+    cancelled: bool,
+}
+
+#[cfg(any(test, all(target_os = "android", feature = "broadcast")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BoundAction {
+    Register,
+    Unregister,
+}
+
+#[cfg(any(test, all(target_os = "android", feature = "broadcast")))]
+impl BoundStatus {
+    // Decides the action for a lifecycle event; nothing is done after cancellation, so a
+    // resume event arriving late doesn't register the receiver again.
+    pub(crate) fn on_lifecycle(&self, is_foreground: bool) -> Option<BoundAction> {
+        if self.cancelled || self.registered == is_foreground {
+            None
+        } else if is_foreground {
+            Some(BoundAction::Register)
+        } else {
+            Some(BoundAction::Unregister)
+        }
+    }
+
+    // Marks the status as cancelled; the receiver is considered unregistered even if
+    // the returned `Unregister` action fails.
+    pub(crate) fn cancel(&mut self) -> Option<BoundAction> {
+        self.cancelled = true;
+        std::mem::take(&mut self.registered).then_some(BoundAction::Unregister)
+    }
+
+    pub(crate) fn done(&mut self, action: BoundAction) {
+        self.registered = action == BoundAction::Register;
+    }
+}
+
 /// Calls [jni_get_vm], attaches the current thread to the JVM and executes the closure;
 /// The thread may stay attached even if it has not been attached previously.
+///
+/// If the closure panics, the pending Java exception (if any) is cleared before the panic
+/// is resumed, so that the JVM stays usable for the thread.
+///
+/// ```
+/// use jni::jni_str;
+/// use jni_min_helper::*;
+///
+/// jni_init_vm_for_unit_test();
+/// let res = std::panic::catch_unwind(|| {
+///     jni_with_env(|env| {
+///         let _ = env.throw_new(jni_str!("java/lang/IllegalStateException"), jni_str!("test"));
+///         assert!(env.exception_check());
+///         panic!("panicked with a pending exception");
+///         #[allow(unreachable_code)]
+///         Ok(())
+///     })
+/// });
+/// assert!(res.is_err());
+/// assert!(!jni_with_env(|env| Ok(env.exception_check())).unwrap());
+/// assert_eq!(jni_current_java_thread_name().unwrap(), "main");
+/// ```
 #[inline(always)]
 pub fn jni_with_env<R>(f: impl FnOnce(&mut Env) -> Result<R, Error>) -> Result<R, Error> {
-    jni_get_vm().attach_current_thread(f)
+    jni_get_vm().attach_current_thread(|env| catch_unwind_clearing_exception(env, f))
+}
+
+/// Works like [jni_with_env], but accepts any error type convertible from `jni::errors::Error`.
+/// A Java exception left pending by the closure is still caught and cleared, and it is
+/// returned as `Error::CaughtJavaException` converted into `E`, no matter what the closure returns.
+///
+/// ```
+/// use jni::{errors::Error, jni_str};
+/// use jni_min_helper::*;
+///
+/// #[derive(Debug)]
+/// enum AppError {
+///     Jni(Error),
+///     Parse(std::num::ParseIntError),
+/// }
+/// impl From<Error> for AppError {
+///     fn from(e: Error) -> Self {
+///         Self::Jni(e)
+///     }
+/// }
+///
+/// jni_init_vm_for_unit_test();
+/// let res = jni_with_env_into(|env| {
+///     let _ = "x".parse::<i32>().map_err(AppError::Parse)?;
+///     Ok(())
+/// });
+/// assert!(matches!(res, Err(AppError::Parse(_))));
+///
+/// let res: Result<(), AppError> = jni_with_env_into(|env| {
+///     env.throw_new(jni_str!("java/lang/IllegalStateException"), jni_str!("test"))?;
+///     Err(Error::JavaException)?
+/// });
+/// let Err(AppError::Jni(Error::CaughtJavaException { name, .. })) = res else {
+///     panic!()
+/// };
+/// assert_eq!(name, "java.lang.IllegalStateException");
+/// assert!(!jni_with_env(|env| Ok(env.exception_check())).unwrap());
+/// ```
+#[inline(always)]
+pub fn jni_with_env_into<R, E: From<Error>>(
+    f: impl FnOnce(&mut Env) -> Result<R, E>,
+) -> Result<R, E> {
+    jni_get_vm().attach_current_thread(|env| catch_unwind_clearing_exception(env, f))
+}
+
+/// Works like [jni_with_env], but the thread is detached after the closure returns if it was
+/// not attached before the call. This is for short-lived threads like those spawned inside
+/// `std::thread::scope`, which should not stay attached after doing JNI work.
+///
+/// Avoid mixing it with [jni_with_env] in the same thread: once the thread is attached
+/// permanently, this function doesn't detach it.
+///
+/// ```
+/// use jni::objects::JString;
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// let words = ["scoped", "threads"];
+/// let lens = std::thread::scope(|s| {
+///     let handles: Vec<_> = words
+///         .iter()
+///         .map(|word| {
+///             s.spawn(move || {
+///                 let len = jni_with_env_scoped(|env| {
+///                     let s = JString::new(env, word)?;
+///                     Ok(s.as_char_sequence().length(env)?)
+///                 });
+///                 assert!(!jni_get_vm().is_thread_attached().unwrap());
+///                 len.unwrap()
+///             })
+///         })
+///         .collect();
+///     handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>()
+/// });
+/// assert_eq!(lens, [6, 7]);
+/// ```
+pub fn jni_with_env_scoped<R>(f: impl FnOnce(&mut Env) -> Result<R, Error>) -> Result<R, Error> {
+    jni_get_vm().attach_current_thread_for_scope(|env| catch_unwind_clearing_exception(env, f))
+}
+
+/// Works like [jni_with_env], but turns the local reference returned by the closure into a
+/// global reference before the local frame is popped, for the "create an object and keep it"
+/// pattern. The type `T` (like `JString`) is given explicitly; the object is checked against it
+/// by `Env::new_cast_global_ref()`, so use `JObject` for classes not found by `FindClass`.
+/// A null reference is returned as a null global reference.
+///
+/// ```
+/// use jni::objects::JString;
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// let s = jni_with_env_global::<JString>(|env| JString::new(env, "kept")).unwrap();
+/// std::thread::spawn(move || {
+///     let text = jni_with_env(|env| s.try_to_string(env)).unwrap();
+///     assert_eq!(text, "kept");
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+pub fn jni_with_env_global<T: jni::refs::Reference>(
+    f: impl for<'local> FnOnce(&mut Env<'local>) -> Result<T::Kind<'local>, Error>,
+) -> Result<jni::refs::Global<T::GlobalKind>, Error> {
+    jni_with_env(|env| {
+        let obj = f(env)?;
+        env.new_cast_global_ref::<T>(obj)
+    })
+}
+
+// Clears the pending exception before resuming the panic, because unwinding through the
+// attach guard with a pending exception may abort the JVM.
+fn catch_unwind_clearing_exception<R, E>(
+    env: &mut Env,
+    f: impl FnOnce(&mut Env) -> Result<R, E>,
+) -> Result<R, E> {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(env))) {
+        Ok(res) => res,
+        Err(payload) => {
+            env.exception_clear();
+            std::panic::resume_unwind(payload)
+        }
+    }
+}
+
+// Gets the message of a panic payload created by `panic!()`.
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "unknown panic payload"
+    }
+}
+
+/// Gets the name of the current thread seen by the JVM, which is useful for logging.
+/// It attaches the current thread to the JVM if it is not attached.
+///
+/// ```
+/// use jni::objects::{JString, JThread};
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// std::thread::spawn(|| {
+///     jni_with_env(|env| {
+///         let name = JString::new(env, "jni-min-helper-test")?;
+///         JThread::current_thread(env)?.set_name(env, name)
+///     })
+///     .unwrap();
+///     assert_eq!(jni_current_java_thread_name().unwrap(), "jni-min-helper-test");
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+pub fn jni_current_java_thread_name() -> Result<String, Error> {
+    jni_with_env(|env| {
+        let name = jni::objects::JThread::current_thread(env)?.get_name(env)?;
+        Ok(name.to_string())
+    })
+}
+
+/// Reads the Java system property like `java.vm.version` by `System.getProperty()`.
+/// Returns `None` if the property is not set. Values are not cached.
+///
+/// ```
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// assert!(jni_system_property("java.vm.version").unwrap().is_some());
+/// assert_eq!(jni_system_property("jni-min-helper.unset").unwrap(), None);
+/// ```
+pub fn jni_system_property(key: &str) -> Result<Option<String>, Error> {
+    jni_with_env(|env| {
+        let key = jni::objects::JString::new(env, key)?;
+        let value = bindings::JSystem::get_property(env, key)?;
+        Ok((!value.is_null()).then(|| value.to_string()))
+    })
+}
+
+/// Gets BCP-47 tags (like `en-US`) of the user's preferred locales, in the order of preference.
+/// On Android, they are read from the configuration of the current context, which has multiple
+/// locales on API level 24 and above; otherwise it is the single `Locale.getDefault()`.
+///
+/// To follow changes on Android, read it again after receiving the broadcast
+/// `android.intent.action.LOCALE_CHANGED` (check `BroadcastWaiter`).
+///
+/// ```
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// let locales = jni_current_locales().unwrap();
+/// assert_eq!(locales.len(), 1);
+/// assert!(!locales[0].is_empty());
+/// ```
+pub fn jni_current_locales() -> Result<Vec<String>, Error> {
+    jni_with_env(|env| {
+        #[cfg(target_os = "android")]
+        return android::android_current_locales(env);
+        #[cfg(not(target_os = "android"))]
+        {
+            let locale = bindings::JLocale::get_default(env)?;
+            Ok(vec![locale.to_language_tag(env)?.to_string()])
+        }
+    })
+}
+
+/// Java heap statistics returned by [jni_java_heap_stats], in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeapStats {
+    /// Memory currently allocated by the JVM for the heap.
+    pub total: i64,
+    /// Unused part of `total`.
+    pub free: i64,
+    /// Maximum memory the heap may grow to, or `i64::MAX` if there is no limit.
+    pub max: i64,
+}
+
+impl HeapStats {
+    /// Memory used by objects, which is `total - free`.
+    pub fn used(&self) -> i64 {
+        self.total - self.free
+    }
+}
+
+/// Reads heap statistics from `Runtime.getRuntime()`, which is useful for debugging memory
+/// pressure caused by leaked global references.
+///
+/// ```
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// jni_request_gc().unwrap();
+/// let stats = jni_java_heap_stats().unwrap();
+/// assert!(stats.free <= stats.total && stats.total <= stats.max);
+/// assert!(stats.used() > 0);
+/// ```
+pub fn jni_java_heap_stats() -> Result<HeapStats, Error> {
+    jni_with_env(|env| {
+        let runtime = bindings::JRuntime::get_runtime(env)?;
+        Ok(HeapStats {
+            total: runtime.total_memory(env)?,
+            free: runtime.free_memory(env)?,
+            max: runtime.max_memory(env)?,
+        })
+    })
+}
+
+/// Calls `System.gc()` and `System.runFinalization()`. The JVM may ignore the request,
+/// so tests relying on it should retry a few times.
+pub fn jni_request_gc() -> Result<(), Error> {
+    jni_with_env(|env| {
+        bindings::JSystem::gc(env)?;
+        bindings::JSystem::run_finalization(env)
+    })
+}
+
+/// Checks once that `GetVersion` of the current `JNIEnv` returns a plausible JNI version of
+/// 1.6 or above, which is required by this crate; the result is memoized. An implausible value
+/// hints that the `JNIEnv` function table is not what `jni` expects, e.g. if the `JNIEnv` is
+/// passed from code linking another `jni` version; a warning is logged and
+/// `Error::UnsupportedVersion` is returned in that case, as well as for older versions.
+///
+/// ```
+/// use jni_min_helper::*;
+/// jni_init_vm_for_unit_test();
+/// jni_verify_version().unwrap();
+/// ```
+pub fn jni_verify_version() -> Result<(), Error> {
+    use jni::JNIVersion;
+    static VERSION: std::sync::OnceLock<jni::sys::jint> = std::sync::OnceLock::new();
+    let raw = match VERSION.get() {
+        Some(&raw) => raw,
+        None => {
+            let raw = jni_with_env(|env| env.version().map(jni::sys::jint::from))?;
+            *VERSION.get_or_init(|| raw)
+        }
+    };
+    let version = JNIVersion::new(raw);
+    let (major, minor) = (version.major(), version.minor());
+    let plausible = raw as u32 & 0xff00ff00 == 0
+        && match major {
+            1 => (1..=8).contains(&minor),
+            9..100 => minor == 0,
+            _ => false,
+        };
+    if !plausible {
+        warn!(
+            "jni_verify_version: unexpected JNI version {raw:#x}, the `JNIEnv` function table \
+            may be inconsistent (check if multiple `jni` versions are linked)."
+        );
+        return Err(Error::UnsupportedVersion);
+    }
+    if raw < jni::sys::jint::from(JNIVersion::V1_6) {
+        warn!("jni_verify_version: JNI {major}.{minor} is older than the required 1.6.");
+        return Err(Error::UnsupportedVersion);
+    }
+    Ok(())
 }
 
 /// Try to get the `JavaVM` from  `jni::JavaVM::singleton`, otherwise it launches
@@ -64,10 +499,12 @@ pub fn jni_init_vm_for_unit_test() {
     use std::sync::OnceLock;
     static JAVA_VM: OnceLock<jni::JavaVM> = OnceLock::new();
     let _vm = JAVA_VM.get_or_init(|| {
-        let args = jni::InitArgsBuilder::new()
-            .option("-Xcheck:jni")
-            .build()
-            .unwrap();
+        let mut args = jni::InitArgsBuilder::new().option("-Xcheck:jni");
+        // follows the behavior of the `java` launcher, useful for testing optional bindings
+        if let Ok(class_path) = std::env::var("CLASSPATH") {
+            args = args.option(format!("-Djava.class.path={class_path}"));
+        }
+        let args = args.build().unwrap();
         jni::JavaVM::new(args).unwrap()
     });
 }
@@ -85,3 +522,61 @@ pub fn jni_get_vm() -> JavaVM {
     // Safety: as documented in `ndk-context` to obtain the `jni::JavaVM`
     unsafe { jni::JavaVM::from_raw(ctx.vm().cast()) }
 }
+
+#[test]
+fn check_dex_header() {
+    let mut header = [0u8; 0x70];
+    header[..8].copy_from_slice(b"dex\n035\0");
+    assert!(dex_header_is_valid(&header));
+    assert!(!dex_header_is_valid(&header[..0x6f]));
+    assert!(!dex_header_is_valid(&[]));
+    header[..4].copy_from_slice(b"PK\x03\x04");
+    assert!(!dex_header_is_valid(&header));
+    header[..8].copy_from_slice(b"dex\n03x\0");
+    assert!(!dex_header_is_valid(&header));
+    header[..8].copy_from_slice(b"dex\n0359");
+    assert!(!dex_header_is_valid(&header));
+}
+
+#[test]
+fn check_bound_status() {
+    use BoundAction::*;
+    // applies events in order, returning the actions
+    fn run(status: &mut BoundStatus, events: &[Option<bool>]) -> Vec<Option<BoundAction>> {
+        events
+            .iter()
+            .map(|event| {
+                let action = match event {
+                    Some(is_foreground) => status.on_lifecycle(*is_foreground),
+                    None => status.cancel(),
+                };
+                if let (Some(action), Some(_)) = (action, event) {
+                    status.done(action);
+                }
+                action
+            })
+            .collect()
+    }
+    let (resume, pause, cancel) = (Some(true), Some(false), None);
+
+    let mut status = BoundStatus::default();
+    let actions = run(&mut status, &[resume, pause, resume]);
+    assert_eq!(actions, [Some(Register), Some(Unregister), Some(Register)]);
+    assert!(status.registered);
+
+    let mut status = BoundStatus::default();
+    let actions = run(&mut status, &[resume, resume, pause, pause]);
+    assert_eq!(actions, [Some(Register), None, Some(Unregister), None]);
+    assert!(!status.registered);
+
+    // cancelled while resumed, then a late resume event arrives
+    let mut status = BoundStatus::default();
+    let actions = run(&mut status, &[resume, cancel, resume, pause]);
+    assert_eq!(actions, [Some(Register), Some(Unregister), None, None]);
+    assert!(!status.registered);
+
+    let mut status = BoundStatus::default();
+    let actions = run(&mut status, &[cancel, resume, cancel]);
+    assert_eq!(actions, [None, None, None]);
+    assert!(!status.registered);
+}
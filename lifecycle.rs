@@ -0,0 +1,275 @@
+use crate::{
+    android::{android_helper_class_loader, context_is_activity, get_android_context},
+    bindings::JSystem,
+    jni_with_env,
+    proxy::DynamicProxy,
+};
+
+use jni::{
+    Env,
+    errors::Error,
+    objects::{JClass, JObject},
+    refs::{Global, LoaderContext, Reference},
+};
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Condvar, Mutex},
+    task::Waker,
+    time::Duration,
+};
+
+const IMPORTANCE_FOREGROUND: i32 = 100;
+
+jni::bind_java_type! {
+    AndroidApplication => "android.app.Application",
+    type_map = {
+        ActivityLifecycleCallbacks => "android.app.Application$ActivityLifecycleCallbacks",
+    },
+    methods {
+        fn register_activity_lifecycle_callbacks(callback: ActivityLifecycleCallbacks),
+        fn unregister_activity_lifecycle_callbacks(callback: ActivityLifecycleCallbacks),
+    },
+}
+
+jni::bind_java_type! {
+    ActivityLifecycleCallbacks => "android.app.Application$ActivityLifecycleCallbacks",
+}
+
+jni::bind_java_type! {
+    ActivityManager => "android.app.ActivityManager",
+    type_map = {
+        RunningAppProcessInfo => "android.app.ActivityManager$RunningAppProcessInfo",
+    },
+    methods {
+        static fn get_my_memory_state(out_state: RunningAppProcessInfo),
+    },
+}
+
+jni::bind_java_type! {
+    RunningAppProcessInfo => "android.app.ActivityManager$RunningAppProcessInfo",
+    constructors {
+        fn new(),
+    },
+    fields {
+        importance {
+            sig = jint,
+            get = importance,
+        },
+    },
+}
+
+/// Activity lifecycle event received by [LifecycleWatcher], carrying the identity hash code
+/// (`System.identityHashCode()`) of the activity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    Created(i32),
+    Started(i32),
+    Resumed(i32),
+    Paused(i32),
+    Stopped(i32),
+    Destroyed(i32),
+}
+
+/// Watches activity lifecycle events of the current application, registered by
+/// `Application.registerActivityLifecycleCallbacks()` with a `DynamicProxy`;
+/// it is unregistered on `drop()`.
+///
+/// If the application is in the foreground on creation, a `Resumed` event is synthesized
+/// for the current context (its hash is 0 if the context is not an activity).
+///
+/// It implements `futures_core::Stream` with the `futures` feature.
+#[derive(Debug)]
+pub struct LifecycleWatcher {
+    application: Global<AndroidApplication<'static>>,
+    proxy: DynamicProxy,
+    inner: Arc<WatcherInner>,
+}
+
+// Called after each event with the foreground state, in the thread delivering the event.
+type ForegroundListener = dyn Fn(&mut Env, bool) + Send + Sync;
+
+#[derive(Default)]
+struct WatcherInner {
+    state: Mutex<WatcherState>,
+    cond: Condvar,
+    waker: Mutex<Option<Waker>>,
+    listener: Option<Box<ForegroundListener>>,
+}
+
+impl std::fmt::Debug for WatcherInner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatcherInner")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Default)]
+struct WatcherState {
+    events: VecDeque<LifecycleEvent>,
+    resumed: HashSet<i32>,
+}
+
+impl WatcherInner {
+    fn push(&self, env: &mut Env, event: LifecycleEvent) {
+        let mut state = self.state.lock().unwrap();
+        match event {
+            LifecycleEvent::Resumed(hash) => {
+                state.resumed.insert(hash);
+            }
+            LifecycleEvent::Paused(hash) | LifecycleEvent::Destroyed(hash) => {
+                state.resumed.remove(&hash);
+                // removes the synthesized entry if the real activity is not known
+                state.resumed.remove(&0);
+            }
+            _ => (),
+        }
+        state.events.push_back(event);
+        let is_foreground = !state.resumed.is_empty();
+        drop(state);
+        self.cond.notify_all();
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+        if let Some(listener) = &self.listener {
+            listener(env, is_foreground);
+        }
+    }
+}
+
+impl LifecycleWatcher {
+    /// Registers a new watcher to the application of the current Android context.
+    pub fn build() -> Result<Self, Error> {
+        Self::build_with_inner(WatcherInner::default())
+    }
+
+    // Registers a watcher calling `listener` after each event (and the synthesized event).
+    #[cfg(feature = "broadcast")]
+    pub(crate) fn build_with_listener(
+        listener: impl Fn(&mut Env, bool) + Send + Sync + 'static,
+    ) -> Result<Self, Error> {
+        Self::build_with_inner(WatcherInner {
+            listener: Some(Box::new(listener)),
+            ..Default::default()
+        })
+    }
+
+    fn build_with_inner(inner: WatcherInner) -> Result<Self, Error> {
+        let inner = Arc::new(inner);
+        let inner_weak = Arc::downgrade(&inner);
+        jni_with_env(|env| {
+            let context = get_android_context();
+            let application = context.get_application_context(env)?;
+            let application = env.cast_local::<AndroidApplication>(application)?;
+
+            let loader = LoaderContext::Loader(android_helper_class_loader()?);
+            let cls_callbacks = ActivityLifecycleCallbacks::lookup_class(env, &loader)?;
+            use std::ops::Deref;
+            let proxy = DynamicProxy::build(
+                env,
+                &loader,
+                [AsRef::<JClass>::as_ref(&cls_callbacks.deref())],
+                move |env, method, args| {
+                    let Some(inner) = inner_weak.upgrade() else {
+                        return Ok(JObject::null());
+                    };
+                    let event: fn(i32) -> LifecycleEvent =
+                        match method.get_name(env)?.to_string().as_str() {
+                            "onActivityCreated" => LifecycleEvent::Created,
+                            "onActivityStarted" => LifecycleEvent::Started,
+                            "onActivityResumed" => LifecycleEvent::Resumed,
+                            "onActivityPaused" => LifecycleEvent::Paused,
+                            "onActivityStopped" => LifecycleEvent::Stopped,
+                            "onActivityDestroyed" => LifecycleEvent::Destroyed,
+                            // including `onActivitySaveInstanceState` and `onActivityPre*`/`Post*`
+                            _ => return Ok(JObject::null()),
+                        };
+                    let activity = args.get_element(env, 0)?;
+                    let hash = JSystem::identity_hash_code(env, activity)?;
+                    inner.push(env, event(hash));
+                    Ok(JObject::null())
+                },
+            )?;
+
+            let callbacks = env.as_cast::<ActivityLifecycleCallbacks>(proxy.as_ref())?;
+            application.register_activity_lifecycle_callbacks(env, &callbacks)?;
+
+            if is_app_foreground(env)? {
+                let hash = if context_is_activity(env)? {
+                    JSystem::identity_hash_code(env, context)?
+                } else {
+                    0
+                };
+                inner.push(env, LifecycleEvent::Resumed(hash));
+            }
+
+            Ok(Self {
+                application: env.new_global_ref(application)?,
+                proxy,
+                inner,
+            })
+        })
+    }
+
+    /// Returns true if any activity of the application is resumed.
+    pub fn is_foreground(&self) -> bool {
+        !self.inner.state.lock().unwrap().resumed.is_empty()
+    }
+
+    /// Blocks until any activity is resumed or the timeout elapses; returns `is_foreground()`.
+    /// Note: Waiting in the `android_main()` thread will prevent it from receiving events.
+    pub fn wait_resumed(&self, timeout: Duration) -> bool {
+        let state = self.inner.state.lock().unwrap();
+        let (state, _) = self
+            .inner
+            .cond
+            .wait_timeout_while(state, timeout, |s| s.resumed.is_empty())
+            .unwrap();
+        !state.resumed.is_empty()
+    }
+
+    /// Returns the amount of received events available for checking.
+    pub fn count_received(&self) -> usize {
+        self.inner.state.lock().unwrap().events.len()
+    }
+
+    /// Takes the next received event if available.
+    pub fn take_next(&self) -> Option<LifecycleEvent> {
+        self.inner.state.lock().unwrap().events.pop_front()
+    }
+}
+
+impl Drop for LifecycleWatcher {
+    fn drop(&mut self) {
+        let _ = jni_with_env(|env| {
+            let callbacks = env.as_cast::<ActivityLifecycleCallbacks>(self.proxy.as_ref())?;
+            self.application
+                .unregister_activity_lifecycle_callbacks(env, &callbacks)
+        });
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for LifecycleWatcher {
+    type Item = LifecycleEvent;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        // the waker is registered with the state locked, so no event can be missed
+        let mut state = self.inner.state.lock().unwrap();
+        if let Some(event) = state.events.pop_front() {
+            return std::task::Poll::Ready(Some(event));
+        }
+        self.inner.waker.lock().unwrap().replace(cx.waker().clone());
+        std::task::Poll::Pending
+    }
+}
+
+fn is_app_foreground(env: &mut Env<'_>) -> Result<bool, Error> {
+    let info = RunningAppProcessInfo::new(env)?;
+    ActivityManager::get_my_memory_state(env, &info)?;
+    Ok(info.importance(env)? == IMPORTANCE_FOREGROUND)
+}
@@ -0,0 +1,82 @@
+use std::{
+    ffi::{CString, c_char, c_int},
+    sync::Once,
+};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+// <https://developer.android.com/ndk/reference/group/logging>
+const ANDROID_LOG_VERBOSE: c_int = 2;
+const ANDROID_LOG_DEBUG: c_int = 3;
+const ANDROID_LOG_INFO: c_int = 4;
+const ANDROID_LOG_WARN: c_int = 5;
+const ANDROID_LOG_ERROR: c_int = 6;
+
+#[link(name = "log")]
+unsafe extern "C" {
+    fn __android_log_write(prio: c_int, tag: *const c_char, text: *const c_char) -> c_int;
+}
+
+fn log_write(prio: c_int, tag: &str, text: &str) {
+    // interior nul bytes are replaced, instead of dropping the message
+    let tag = CString::new(tag.replace('\0', " ")).unwrap();
+    let text = CString::new(text.replace('\0', " ")).unwrap();
+    // Safety: both strings are valid nul-terminated C strings during the call.
+    unsafe {
+        __android_log_write(prio, tag.as_ptr(), text.as_ptr());
+    }
+}
+
+struct AndroidLogger {
+    tag: String,
+}
+
+impl Log for AndroidLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let prio = match record.level() {
+            Level::Error => ANDROID_LOG_ERROR,
+            Level::Warn => ANDROID_LOG_WARN,
+            Level::Info => ANDROID_LOG_INFO,
+            Level::Debug => ANDROID_LOG_DEBUG,
+            Level::Trace => ANDROID_LOG_VERBOSE,
+        };
+        log_write(prio, &self.tag, &record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs a minimal logger writing to logcat with the given `tag`, for messages produced
+/// by this crate and the application (through the `log` crate).
+///
+/// Returns an error if another logger has been installed.
+pub fn init_android_logger(tag: &str, level: LevelFilter) -> Result<(), log::SetLoggerError> {
+    let logger = Box::leak(Box::new(AndroidLogger {
+        tag: tag.to_string(),
+    }));
+    log::set_logger(logger)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+// Called by `warn!`: tells the developer once that messages of this crate are discarded.
+pub(crate) fn check_logger() {
+    static CHECKED: Once = Once::new();
+    CHECKED.call_once(|| {
+        if log::max_level() == LevelFilter::Off {
+            log_write(
+                ANDROID_LOG_WARN,
+                "jni-min-helper",
+                "No logger is installed, warnings of jni-min-helper are discarded; \
+                 call `init_android_logger()` or initialize another logger.",
+            );
+        }
+    });
+}
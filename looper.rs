@@ -0,0 +1,123 @@
+use crate::{
+    jni_with_env,
+    proxy::{DynamicProxy, JRunnable},
+};
+
+use jni::{
+    Env,
+    errors::Error,
+    objects::{JObject, JString, JThread},
+    refs::Global,
+};
+use std::{sync::mpsc, thread::JoinHandle};
+
+jni::bind_java_type! {
+    pub(crate) AndroidLooper => "android.os.Looper",
+    methods {
+        static fn get_main_looper() -> AndroidLooper,
+        static fn prepare(),
+        static fn my_looper() -> AndroidLooper,
+        static fn run_loop {
+            name = "loop",
+            sig = () -> (),
+        },
+        fn quit_safely(), // API level >= 18
+    }
+}
+
+jni::bind_java_type! {
+    pub(crate) AndroidHandler => "android.os.Handler",
+    type_map = {
+        AndroidLooper => "android.os.Looper",
+        JRunnable => "java.lang.Runnable",
+    },
+    constructors {
+        fn new(looper: AndroidLooper),
+    },
+    methods {
+        fn post(r: JRunnable) -> jboolean,
+    }
+}
+
+/// Rust thread running an `android.os.Looper`, which can be the target of `Handler` objects
+/// for callbacks that must not run in the main thread, without creating a Java `HandlerThread`.
+///
+/// On `drop()`, the looper quits safely: messages already due are processed, then pending
+/// delayed messages are dropped; the thread is joined and then detached from the JVM as it exits.
+/// Do not drop it inside a callback running on the looper thread itself, which will deadlock.
+#[derive(Debug)]
+pub struct LooperThread {
+    looper: Global<AndroidLooper<'static>>,
+    handler: Global<AndroidHandler<'static>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LooperThread {
+    /// Spawns the thread named `name` (also set as the Java thread name), prepares the looper
+    /// and returns after it is ready.
+    pub fn spawn(name: &str) -> Result<Self, Error> {
+        let (tx, rx) = mpsc::channel();
+        let java_name = name.to_string();
+        let thread = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                // `jni_with_env` keeps the thread attached until it exits
+                let result = jni_with_env(|env| {
+                    let java_name = JString::new(env, &java_name)?;
+                    JThread::current_thread(env)?.set_name(env, java_name)?;
+                    AndroidLooper::prepare(env)?;
+                    let looper = AndroidLooper::my_looper(env)?;
+                    let handler = AndroidHandler::new(env, &looper)?;
+                    Ok((env.new_global_ref(looper)?, env.new_global_ref(handler)?))
+                });
+                let is_ready = result.is_ok();
+                let _ = tx.send(result);
+                if is_ready {
+                    let _ = jni_with_env(|env| {
+                        let result = AndroidLooper::run_loop(env);
+                        env.exception_clear();
+                        result
+                    });
+                }
+            })
+            .map_err(|_| Error::NullPtr("failed to spawn the looper thread"))?;
+        let (looper, handler) = match rx.recv() {
+            Ok(result) => result?,
+            Err(_) => return Err(Error::NullPtr("the looper thread exited unexpectedly")),
+        };
+        Ok(Self {
+            looper,
+            handler,
+            thread: Some(thread),
+        })
+    }
+
+    /// Gets the `android.os.Looper` of the thread.
+    pub fn looper(&self) -> &JObject<'static> {
+        self.looper.as_obj()
+    }
+
+    /// Gets the `android.os.Handler` created for the looper, which can be passed to Java APIs
+    /// like `Context.registerReceiver(receiver, filter, permission, scheduler)`.
+    pub fn handler(&self) -> &JObject<'static> {
+        self.handler.as_obj()
+    }
+
+    /// Posts the closure to be called once in the looper thread.
+    /// Returns false on failure (usually because the looper is exiting).
+    pub fn post(
+        &self,
+        runnable: impl Fn(&mut Env) -> Result<(), Error> + Send + Sync + 'static,
+    ) -> Result<bool, Error> {
+        jni_with_env(|env| DynamicProxy::post_to_handler(env, &self.handler, runnable))
+    }
+}
+
+impl Drop for LooperThread {
+    fn drop(&mut self) {
+        let _ = jni_with_env(|env| self.looper.quit_safely(env));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
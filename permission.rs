@@ -1,14 +1,14 @@
-use std::sync::Mutex;
-
-#[cfg(not(feature = "futures"))]
-use std::sync::mpsc::{Receiver, Sender, channel};
-
-#[cfg(feature = "futures")]
-use futures_channel::oneshot::{Receiver, Sender, channel};
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    task::Waker,
+    time::Duration,
+};
 
 use crate::{
-    android::{android_api_level, get_android_context, get_helper_class_loader},
-    jni_with_env,
+    android::{
+        AndroidFeature, android_feature_available, android_helper_class_loader, get_android_context,
+    },
+    jni_new_string_array, jni_read_primitive_array, jni_read_string_array, jni_with_env,
     receiver::Intent,
 };
 
@@ -22,6 +22,8 @@ use jni::{
 const PERMISSION_GRANTED: i32 = 0;
 const EXTRA_PERM_ARRAY: &str = "rust.jniminhelper.perm_array";
 const EXTRA_TITLE: &str = "rust.jniminhelper.perm_activity_title";
+#[cfg(feature = "broadcast")]
+const ACTION_PERM_RESULT: &str = "rust.jniminhelper.PERM_RESULT";
 
 jni::bind_java_type! {
     PermActivity => "rust.jniminhelper.PermActivity",
@@ -32,7 +34,25 @@ jni::bind_java_type! {
 
 type RequestResult = Vec<(String, bool)>;
 
-static MUTEX_PERM_REQ: Mutex<Option<Sender<RequestResult>>> = Mutex::new(None);
+static MUTEX_PERM_REQ: Mutex<Option<Arc<RequestState>>> = Mutex::new(None);
+
+// Shared by `PermissionRequest` and the callbacks, which take it from `MUTEX_PERM_REQ` to send.
+#[derive(Default)]
+struct RequestState {
+    result: Mutex<Option<RequestResult>>,
+    cond: Condvar,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl RequestState {
+    fn send(&self, result: RequestResult) {
+        self.result.lock().unwrap().replace(result);
+        self.cond.notify_all();
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
 
 /// Android runtime permission request utility.
 ///
@@ -42,15 +62,22 @@ static MUTEX_PERM_REQ: Mutex<Option<Sender<RequestResult>>> = Mutex::new(None);
 ///
 /// For native activity applications, `cargo-apk` does not support these things at the time of
 /// publishing this version of `jni-min-helper` (`cargo-apk2` has introduced these features).
+///
+/// With the `broadcast` feature, `PermActivity` also sends a broadcast to this package after the
+/// request, in case that the native callback is not received on some devices. The receiver may
+/// be exported below API level 33, so the broadcast's extras are not trusted: the result is
+/// checked again by [PermissionRequest::has_permission] when it is received.
 pub struct PermissionRequest {
-    receiver: Receiver<RequestResult>,
+    state: Arc<RequestState>,
+    #[cfg(feature = "broadcast")]
+    _result_receiver: Option<crate::BroadcastReceiver>,
 }
 
 impl PermissionRequest {
     /// Checks if a permission is already granted.
     /// Returns `Error::MethodNotFound` if the Android API level is less than 23.
     pub fn has_permission(permission: &str) -> Result<bool, Error> {
-        if android_api_level() < 23 {
+        if !android_feature_available(AndroidFeature::RuntimePermissions) {
             return Err(Error::MethodNotFound {
                 name: "checkSelfPermission".to_string(),
                 sig: "Android API level < 23".to_string(),
@@ -78,7 +105,7 @@ impl PermissionRequest {
         title: &str,
         permissions: impl IntoIterator<Item = &'a str>,
     ) -> Result<Option<Self>, Error> {
-        if android_api_level() < 23 {
+        if !android_feature_available(AndroidFeature::RuntimePermissions) {
             return Ok(None);
         }
         if Self::is_pending() {
@@ -95,8 +122,15 @@ impl PermissionRequest {
             return Ok(None);
         }
 
-        let receiver = jni_with_env(|env| {
-            let loader = jni::refs::LoaderContext::Loader(get_helper_class_loader()?);
+        crate::check_helper_class("PermActivity")?;
+
+        #[cfg(feature = "broadcast")]
+        let result_receiver = result_broadcast::register_result_receiver(perms.clone())
+            .inspect_err(|e| warn!("Failed to register the permission result receiver: {e:?}"))
+            .ok();
+
+        let state = jni_with_env(|env| {
+            let loader = jni::refs::LoaderContext::Loader(android_helper_class_loader()?);
             let _ = PermActivityAPI::get(env, &loader)?;
             let cls_perm = PermActivity::lookup_class(env, &loader)?;
 
@@ -109,53 +143,65 @@ impl PermissionRequest {
             let title = JString::new(env, title)?;
             intent.put_extra_string(env, extra_title, title)?;
 
-            let arr_perms = JObjectArray::<JString>::new(env, perms.len(), JString::null())?;
-            for (i, perm) in perms.iter().enumerate() {
-                let perm = JString::new(env, perm)?;
-                arr_perms.set_element(env, i, perm)?;
-            }
+            let arr_perms = jni_new_string_array(env, &perms)?;
             let extra_perm_array = JString::new(env, EXTRA_PERM_ARRAY)?;
             intent.put_extra_string_array(env, &extra_perm_array, &arr_perms)?;
 
-            let (tx, rx) = channel();
-            MUTEX_PERM_REQ.lock().unwrap().replace(tx);
+            let state = Arc::new(RequestState::default());
+            MUTEX_PERM_REQ.lock().unwrap().replace(state.clone());
 
             context.start_activity(env, &intent)?;
-            Ok(rx)
+            Ok(state)
         })
         .inspect_err(|_| {
             let _ = MUTEX_PERM_REQ.lock().unwrap().take();
         })?;
 
-        Ok(Some(Self { receiver }))
+        Ok(Some(Self {
+            state,
+            #[cfg(feature = "broadcast")]
+            _result_receiver: result_receiver,
+        }))
     }
 
     /// Blocks on waiting the permission request and returns the result.
     ///
-    /// Warning: Blocking in the `android_main()` thread will block the future's completion if it
+    /// Warning: Blocking in the `android_main()` thread will block the request's completion if it
     /// depends on event processing in this thread (check your glue crate like `android_activity`).
     pub fn wait(self) -> RequestResult {
-        #[cfg(not(feature = "futures"))]
-        {
-            self.receiver.recv().unwrap_or_default()
-        }
-        #[cfg(feature = "futures")]
-        {
-            futures_lite::future::block_on(self).unwrap_or_default()
-        }
+        let result = self.state.result.lock().unwrap();
+        let mut result = self.state.cond.wait_while(result, |r| r.is_none()).unwrap();
+        result.take().unwrap()
+    }
+
+    /// Blocks on waiting the permission request until the timeout elapses; returns `None` on
+    /// timeout, then it may be waited again. Check [PermissionRequest::wait] for the warning.
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<RequestResult> {
+        let result = self.state.result.lock().unwrap();
+        let (mut result, _) = self
+            .state
+            .cond
+            .wait_timeout_while(result, timeout, |r| r.is_none())
+            .unwrap();
+        result.take()
     }
 }
 
 #[cfg(feature = "futures")]
 impl std::future::Future for PermissionRequest {
-    type Output = Result<RequestResult, futures_channel::oneshot::Canceled>;
+    type Output = RequestResult;
 
     fn poll(
-        mut self: std::pin::Pin<&mut Self>,
+        self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Self::Output> {
-        use futures_lite::FutureExt;
-        self.receiver.poll(cx)
+        // the waker is registered with the result locked, so `send()` cannot be missed
+        let mut result = self.state.result.lock().unwrap();
+        if let Some(result) = result.take() {
+            return std::task::Poll::Ready(result);
+        }
+        self.state.waker.lock().unwrap().replace(cx.waker().clone());
+        std::task::Poll::Pending
     }
 }
 
@@ -175,24 +221,91 @@ impl PermActivityNativeInterface for PermActivityAPI {
         if permissions.is_null() || grant_results.is_null() {
             // it should be unreachable
             warn!("Unexpected: perm_callback() received null.");
-            let _ = sender.send(Vec::new());
+            sender.send(Vec::new());
             return Err(Error::NullPtr("Unexpected: perm_callback() received null."));
         }
 
-        let mut result = Vec::new();
+        let result = read_request_result(env, &permissions, &grant_results);
+        sender.send(result.as_ref().cloned().unwrap_or_default());
+        result.map(|_| ())
+    }
+}
 
-        let mut grant_vals = vec![0; grant_results.len(env)?];
-        grant_results.get_region(env, 0, &mut grant_vals)?;
-        for (i, &res_val) in grant_vals.iter().enumerate() {
-            result.push((
-                permissions.get_element(env, i)?.to_string(),
-                res_val == PERMISSION_GRANTED,
-            ));
-        }
+fn read_request_result<'local>(
+    env: &mut Env<'local>,
+    permissions: &JObjectArray<'local, JString<'local>>,
+    grant_results: &JIntArray<'local>,
+) -> Result<RequestResult, Error> {
+    let permissions = jni_read_string_array(env, permissions)?;
+    let grant_vals = jni_read_primitive_array(env, grant_results)?;
+    let result = permissions
+        .into_iter()
+        .zip(grant_vals)
+        .map(|(perm, res_val)| (perm, res_val == PERMISSION_GRANTED))
+        .collect();
+    Ok(result)
+}
 
-        if let Err(e) = sender.send(result) {
-            warn!("Error in perm_callback(): sender.send() failed: {e:?}.");
-        }
-        Ok(())
+#[cfg(feature = "broadcast")]
+mod result_broadcast {
+    use super::*;
+    use crate::{
+        BroadcastReceiver,
+        receiver::{AndroidBroadcastReceiver, IntentFilter},
+    };
+    use jni::refs::Cast;
+
+    const RECEIVER_NOT_EXPORTED: i32 = 4;
+
+    jni::bind_java_type! {
+        AndroidContextApi33 => "android.content.Context",
+        type_map = {
+            AndroidBroadcastReceiver => "android.content.BroadcastReceiver",
+            Intent => "android.content.Intent",
+            IntentFilter => "android.content.IntentFilter",
+        },
+        methods {
+            fn register_receiver {
+                name = "registerReceiver",
+                sig = (receiver: AndroidBroadcastReceiver, filter: IntentFilter, flags: jint) -> Intent,
+            },
+        },
+    }
+
+    // The native callback is invoked before sending this broadcast, so the handler only
+    // delivers the result if it is still pending. Below API level 33 the receiver is exported
+    // and any app may send the action, so the extras are ignored and the result is rebuilt
+    // from `checkSelfPermission()` for each requested permission.
+    pub(super) fn register_result_receiver(
+        permissions: Vec<String>,
+    ) -> Result<BroadcastReceiver, Error> {
+        let receiver = BroadcastReceiver::build(move |_, _, _| {
+            let Some(sender) = MUTEX_PERM_REQ.lock().unwrap().take() else {
+                return Ok(());
+            };
+            let result: Result<RequestResult, Error> = permissions
+                .iter()
+                .map(|perm| Ok((perm.clone(), PermissionRequest::has_permission(perm)?)))
+                .collect();
+            sender.send(result.as_ref().cloned().unwrap_or_default());
+            result.map(|_| ())
+        })?;
+
+        jni_with_env(|env| {
+            let action = JString::new(env, ACTION_PERM_RESULT)?;
+            let filter = IntentFilter::new_with_action(env, action)?;
+            if android_feature_available(AndroidFeature::ReceiverExportFlag) {
+                // Safety: `AndroidContext` and `AndroidContextApi33` both map to `android.content.Context`.
+                let context: Cast<'_, '_, AndroidContextApi33> =
+                    unsafe { env.as_cast_unchecked(get_android_context()) };
+                let receiver_obj: Cast<'_, '_, AndroidBroadcastReceiver> =
+                    env.as_cast(&*receiver)?;
+                context.register_receiver(env, receiver_obj, filter, RECEIVER_NOT_EXPORTED)?;
+                Ok(())
+            } else {
+                receiver.register(&filter)
+            }
+        })?;
+        Ok(receiver)
     }
 }
@@ -0,0 +1,190 @@
+use crate::{
+    android::{
+        AndroidFeature, android_feature_available, android_power_manager, android_system_service,
+        get_android_context,
+    },
+    jni_with_env,
+};
+
+use jni::{
+    errors::Error,
+    objects::JString,
+    refs::{Cast, Global, Reference},
+};
+
+use std::time::Duration;
+
+const PARTIAL_WAKE_LOCK: i32 = 1;
+const PERMISSION_GRANTED: i32 = 0;
+const VIBRATION_DEFAULT_AMPLITUDE: i32 = -1;
+
+jni::bind_java_type! {
+    PowerManager => "android.os.PowerManager",
+    type_map = {
+        AndroidWakeLock => "android.os.PowerManager$WakeLock",
+    },
+    methods {
+        fn new_wake_lock(level_and_flags: jint, tag: JString) -> AndroidWakeLock,
+    },
+}
+
+jni::bind_java_type! {
+    AndroidWakeLock => "android.os.PowerManager$WakeLock",
+    methods {
+        fn acquire(),
+        fn acquire_timeout {
+            name = "acquire",
+            sig = (timeout: jlong),
+        },
+        fn release(),
+        fn is_held() -> jboolean,
+        fn set_reference_counted(value: jboolean),
+    },
+}
+
+jni::bind_java_type! {
+    AndroidVibrator => "android.os.Vibrator",
+    methods {
+        fn has_vibrator() -> jboolean,
+        fn vibrate {
+            sig = (milliseconds: jlong),
+        },
+        fn cancel(),
+    },
+}
+
+jni::bind_java_type! {
+    AndroidVibratorApi26 => "android.os.Vibrator",
+    type_map = {
+        AndroidVibrationEffect => "android.os.VibrationEffect",
+    },
+    methods {
+        fn vibrate {
+            sig = (effect: AndroidVibrationEffect),
+        },
+    },
+}
+
+jni::bind_java_type! {
+    AndroidVibrationEffect => "android.os.VibrationEffect",
+    methods {
+        static fn create_one_shot(milliseconds: jlong, amplitude: jint) -> AndroidVibrationEffect,
+    },
+}
+
+jni::bind_java_type! {
+    AndroidVibratorManager => "android.os.VibratorManager",
+    type_map = {
+        AndroidVibrator => "android.os.Vibrator",
+    },
+    methods {
+        fn get_default_vibrator() -> AndroidVibrator,
+    },
+}
+
+/// Holds a `PARTIAL_WAKE_LOCK`, which keeps the CPU running while the screen is off;
+/// it is released on `drop()`.
+///
+/// It requires the `android.permission.WAKE_LOCK` permission declared in `AndroidManifest.xml`.
+#[must_use = "the wake lock is released immediately if it is dropped"]
+#[derive(Debug)]
+pub struct WakeLock {
+    lock: Global<AndroidWakeLock<'static>>,
+}
+
+impl WakeLock {
+    /// Acquires a new wake lock. The `tag` is shown in system diagnostics, like `app:task`.
+    /// With a `timeout`, the lock is released by the system after the duration.
+    ///
+    /// Returns `Error::SecurityViolation` if the `WAKE_LOCK` permission is not granted.
+    pub fn acquire(tag: &str, timeout: Option<Duration>) -> Result<Self, Error> {
+        let power_manager = android_power_manager()?;
+        jni_with_env(|env| {
+            let permission = JString::new(env, "android.permission.WAKE_LOCK")?;
+            let context = get_android_context();
+            if context.check_calling_or_self_permission(env, permission)? != PERMISSION_GRANTED {
+                warn!("WakeLock: `android.permission.WAKE_LOCK` is not granted.");
+                return Err(Error::SecurityViolation);
+            }
+            let power_manager = env.new_local_ref(&power_manager)?;
+            let power_manager = PowerManager::cast_local(env, power_manager)?;
+            let tag = JString::new(env, tag)?;
+            let lock = power_manager.new_wake_lock(env, PARTIAL_WAKE_LOCK, tag)?;
+            // `release()` would throw after the timeout if the lock is reference counted
+            lock.set_reference_counted(env, false)?;
+            if let Some(timeout) = timeout {
+                let millis = timeout.as_millis().min(i64::MAX as u128) as i64;
+                lock.acquire_timeout(env, millis)?;
+            } else {
+                lock.acquire(env)?;
+            }
+            Ok(Self {
+                lock: env.new_global_ref(lock)?,
+            })
+        })
+    }
+
+    /// Returns true if the lock is still held (not released after the timeout).
+    pub fn is_held(&self) -> bool {
+        jni_with_env(|env| self.lock.is_held(env)).unwrap_or(false)
+    }
+}
+
+impl Drop for WakeLock {
+    fn drop(&mut self) {
+        let _ = jni_with_env(|env| {
+            if self.lock.is_held(env)? {
+                self.lock.release(env)?;
+            }
+            Ok::<_, Error>(())
+        });
+    }
+}
+
+/// Vibrates the default vibrator for the `duration` with the default amplitude, returning
+/// immediately. It uses `VibratorManager` on API level 31 or above, and `VibrationEffect`
+/// on API level 26 or above. Returns false if the device has no vibrator.
+///
+/// It requires the `android.permission.VIBRATE` permission declared in `AndroidManifest.xml`;
+/// returns `Error::SecurityViolation` if it is not granted.
+pub fn android_vibrate(duration: Duration) -> Result<bool, Error> {
+    let service = if android_feature_available(AndroidFeature::VibratorManager) {
+        android_system_service("vibrator_manager")?
+    } else {
+        android_system_service("vibrator")?
+    };
+    jni_with_env(|env| {
+        let permission = JString::new(env, "android.permission.VIBRATE")?;
+        let context = get_android_context();
+        if context.check_calling_or_self_permission(env, permission)? != PERMISSION_GRANTED {
+            warn!("android_vibrate: `android.permission.VIBRATE` is not granted.");
+            return Err(Error::SecurityViolation);
+        }
+        let vibrator = if android_feature_available(AndroidFeature::VibratorManager) {
+            let manager = env.new_local_ref(&service)?;
+            let manager = AndroidVibratorManager::cast_local(env, manager)?;
+            manager.get_default_vibrator(env)?
+        } else {
+            let vibrator = env.new_local_ref(&service)?;
+            AndroidVibrator::cast_local(env, vibrator)?
+        };
+        if vibrator.is_null() || !vibrator.has_vibrator(env)? {
+            return Ok(false);
+        }
+        let millis = duration.as_millis().min(i64::MAX as u128) as i64;
+        if millis == 0 {
+            return Ok(true);
+        }
+        if android_feature_available(AndroidFeature::VibrationEffect) {
+            let effect =
+                AndroidVibrationEffect::create_one_shot(env, millis, VIBRATION_DEFAULT_AMPLITUDE)?;
+            // Safety: `AndroidVibrator` and `AndroidVibratorApi26` both map to `android.os.Vibrator`.
+            let vibrator: Cast<'_, '_, AndroidVibratorApi26> =
+                unsafe { env.as_cast_unchecked(&vibrator) };
+            vibrator.vibrate(env, effect)?;
+        } else {
+            vibrator.vibrate(env, millis)?;
+        }
+        Ok(true)
+    })
+}
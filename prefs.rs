@@ -0,0 +1,179 @@
+use crate::{
+    android::{android_helper_class_loader, get_android_context},
+    jni_with_env,
+    proxy::DynamicProxy,
+};
+
+use jni::{
+    errors::Error,
+    objects::{JClass, JObject, JString},
+    refs::{Global, LoaderContext, Reference},
+};
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex},
+    task::Waker,
+    time::Duration,
+};
+
+const MODE_PRIVATE: i32 = 0;
+
+jni::bind_java_type! {
+    pub(crate) SharedPreferences => "android.content.SharedPreferences",
+    type_map = {
+        OnSharedPreferenceChangeListener => "android.content.SharedPreferences$OnSharedPreferenceChangeListener",
+        SharedPreferencesEditor => "android.content.SharedPreferences$Editor",
+    },
+    methods {
+        fn register_on_shared_preference_change_listener(listener: OnSharedPreferenceChangeListener),
+        fn unregister_on_shared_preference_change_listener(listener: OnSharedPreferenceChangeListener),
+        fn edit() -> SharedPreferencesEditor,
+    },
+}
+
+jni::bind_java_type! {
+    pub(crate) SharedPreferencesEditor => "android.content.SharedPreferences$Editor",
+    methods {
+        fn put_string(key: JString, value: JString) -> SharedPreferencesEditor,
+        fn commit() -> jboolean,
+    },
+}
+
+jni::bind_java_type! {
+    OnSharedPreferenceChangeListener => "android.content.SharedPreferences$OnSharedPreferenceChangeListener",
+}
+
+/// Watches changes of the application's `SharedPreferences` file, yielding the changed keys.
+/// The key is `None` if the preferences are cleared (Android 11 and above).
+///
+/// The framework holds the listener weakly, so it is kept alive by the watcher until `drop()`,
+/// then it is unregistered. Listeners are called in the main thread.
+///
+/// It implements `futures_core::Stream` with the `futures` feature.
+#[derive(Debug)]
+pub struct PrefsWatcher {
+    prefs: Global<SharedPreferences<'static>>,
+    listener: DynamicProxy,
+    inner: Arc<PrefsWatcherInner>,
+}
+
+#[derive(Debug, Default)]
+struct PrefsWatcherInner {
+    keys: Mutex<VecDeque<Option<String>>>,
+    cond: Condvar,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl PrefsWatcher {
+    /// Registers a new watcher for the private preferences file of `name`, which is the file
+    /// opened by `Context.getSharedPreferences(name, Context.MODE_PRIVATE)`.
+    pub fn watch(name: &str) -> Result<Self, Error> {
+        let inner = Arc::new(PrefsWatcherInner::default());
+        let inner_weak = Arc::downgrade(&inner);
+        jni_with_env(|env| {
+            let name = JString::new(env, name)?;
+            let prefs = get_android_context().get_shared_preferences(env, name, MODE_PRIVATE)?;
+            if prefs.is_null() {
+                return Err(Error::NullPtr(
+                    "Context.getSharedPreferences() returned null",
+                ));
+            }
+
+            // the interface is loaded from the boot class path, so any loader can define the
+            // proxy class; the helper loader is used like other watchers, which ensures that
+            // the embedded dex (including the `InvocHdl` class used by the proxy) is loaded.
+            let loader = LoaderContext::Loader(android_helper_class_loader()?);
+            let cls_listener = OnSharedPreferenceChangeListener::lookup_class(env, &loader)?;
+            use std::ops::Deref;
+            let listener = DynamicProxy::build(
+                env,
+                &loader,
+                [AsRef::<JClass>::as_ref(&cls_listener.deref())],
+                move |env, method, args| {
+                    let Some(inner) = inner_weak.upgrade() else {
+                        return Ok(JObject::null());
+                    };
+                    if &method.get_name(env)?.to_string() != "onSharedPreferenceChanged" {
+                        return Ok(JObject::null());
+                    }
+                    let key = args.get_element(env, 1)?;
+                    let key = if key.is_null() {
+                        None
+                    } else {
+                        Some(JString::cast_local(env, key)?.to_string())
+                    };
+                    inner.keys.lock().unwrap().push_back(key);
+                    inner.cond.notify_all();
+                    if let Some(waker) = inner.waker.lock().unwrap().take() {
+                        waker.wake();
+                    }
+                    Ok(JObject::null())
+                },
+            )?;
+
+            let callback = env.as_cast::<OnSharedPreferenceChangeListener>(listener.as_ref())?;
+            prefs.register_on_shared_preference_change_listener(env, &callback)?;
+            Ok(Self {
+                prefs: env.new_global_ref(prefs)?,
+                listener,
+                inner,
+            })
+        })
+    }
+
+    /// Returns the amount of received keys available for checking.
+    pub fn count_received(&self) -> usize {
+        self.inner.keys.lock().unwrap().len()
+    }
+
+    /// Takes the next changed key if available.
+    pub fn take_next(&self) -> Option<Option<String>> {
+        self.inner.keys.lock().unwrap().pop_front()
+    }
+
+    /// Waits for receiving a changed key.
+    /// Note: Waiting in the main thread will prevent it from receiving.
+    pub fn wait_timeout(&self, timeout: Duration) -> Option<Option<String>> {
+        let keys = self.inner.keys.lock().unwrap();
+        let (mut keys, _) = self
+            .inner
+            .cond
+            .wait_timeout_while(keys, timeout, |q| q.is_empty())
+            .unwrap();
+        keys.pop_front()
+    }
+}
+
+impl Drop for PrefsWatcher {
+    fn drop(&mut self) {
+        let _ = jni_with_env(|env| {
+            let callback =
+                env.as_cast::<OnSharedPreferenceChangeListener>(self.listener.as_ref())?;
+            self.prefs
+                .unregister_on_shared_preference_change_listener(env, &callback)
+        });
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for PrefsWatcher {
+    type Item = Option<String>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        // the waker is registered with the queue locked, so no key can be missed
+        let mut keys = self.inner.keys.lock().unwrap();
+        if let Some(key) = keys.pop_front() {
+            return std::task::Poll::Ready(Some(key));
+        }
+        self.inner.waker.lock().unwrap().replace(cx.waker().clone());
+        std::task::Poll::Pending
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count_received(), None)
+    }
+}
@@ -6,15 +6,21 @@ use jni::{
     errors::Error,
     jni_str,
     objects::{JClass, JClassLoader, JObject, JObjectArray},
-    refs::{Global, LoaderContext},
+    refs::{Global, LoaderContext, Reference, Weak},
+    strings::JNIString,
     sys::jlong,
 };
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     collections::HashMap,
+    future::Future,
     mem::forget,
-    sync::{Arc, LazyLock, Mutex},
-    time::Instant,
+    panic::AssertUnwindSafe,
+    pin::pin,
+    sync::{Arc, LazyLock, Mutex, Once},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::{Duration, Instant},
 };
 
 #[cfg(not(target_os = "android"))]
@@ -47,13 +53,19 @@ jni::bind_java_type! {
     },
     hooks = {
         load_class = |env, load_context, initialize| {
+            crate::check_helper_class("InvocHdl")?;
             #[cfg(not(target_os = "android"))]
             let class_loader = {
-                let class_loader = match load_context {
+                let parent = match load_context {
                     LoaderContext::Loader(loader) => env.new_local_ref(loader)?,
                     LoaderContext::FromObject(obj) => env.get_object_class(obj)?.get_class_loader(env)?,
                     LoaderContext::None => JClassLoader::get_system_class_loader(env)?,
                 };
+                // Defines the class in a private loader, so that multiple libraries embedding
+                // this crate in the same process never share (and re-register) the native method.
+                // On Android, each library loads its own dex with a separate loader.
+                let urls = JObjectArray::<JUrl>::new(env, 0, JUrl::null())?;
+                let class_loader: JClassLoader = JUrlClassLoader::new(env, &urls, &parent)?.into();
                 env.define_class(
                     Some(jni::jni_str!("rust/jniminhelper/InvocHdl")),
                     &class_loader,
@@ -64,7 +76,7 @@ jni::bind_java_type! {
             #[cfg(target_os = "android")]
             let class_loader = {
                 let _ = load_context;
-                crate::get_helper_class_loader()?
+                crate::android_helper_class_loader()?
             };
             #[allow(clippy::needless_borrow)]
             let loader_context = LoaderContext::Loader(&class_loader);
@@ -73,40 +85,45 @@ jni::bind_java_type! {
     },
 }
 
-#[cfg(target_os = "android")]
+#[cfg(not(target_os = "android"))]
 jni::bind_java_type! {
-    JRunnable => "java.lang.Runnable",
+    JUrlClassLoader => "java.net.URLClassLoader",
+    type_map = {
+        JUrl => "java.net.URL",
+    },
+    constructors {
+        fn new(urls: JUrl[], parent: JClassLoader),
+    },
+    is_instance_of = {
+        JClassLoader,
+    },
 }
 
-#[cfg(target_os = "android")]
+#[cfg(not(target_os = "android"))]
 jni::bind_java_type! {
-    AndroidLooper => "android.os.Looper",
-    methods {
-        static fn get_main_looper() -> AndroidLooper,
-    }
+    JUrl => "java.net.URL",
 }
 
 #[cfg(target_os = "android")]
 jni::bind_java_type! {
-    AndroidHandler => "android.os.Handler",
-    type_map = {
-        AndroidLooper => "android.os.Looper",
-        JRunnable => "java.lang.Runnable",
-    },
-    constructors {
-        fn new(looper: AndroidLooper),
-    },
-    methods {
-        fn post(r: JRunnable) -> jboolean,
-    }
+    pub(crate) JRunnable => "java.lang.Runnable",
 }
 
+#[cfg(target_os = "android")]
+use crate::looper::{AndroidHandler, AndroidLooper};
+
 // Maps Java invocation handler IDs to Rust closures.
 // `LazyLock` is required for a const initializer.
 // `Arc` is required for having `dyn` closures and using them after dropping the MutexGuard.
 static RUST_HANDLERS: LazyLock<Mutex<HashMap<i64, Arc<RustHandler>>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
+// Maps handler IDs to weak references of their proxy objects, for `DynamicProxy::ret_self`;
+// only proxies registered by `DynamicProxy::enable_ret_self` are kept here.
+// Weak references avoid keeping proxy objects alive through this map.
+static PROXY_OBJECTS: LazyLock<Mutex<HashMap<i64, Weak<JObject<'static>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 // The lifetime sugar cannot apply here, because the closure requires multiple reference
 // as parameters. Reference: <https://doc.rust-lang.org/stable/nomicon/hrtb.html>.
 // Requiring all references here to have the same lifetime bounds doesn't introduce
@@ -122,6 +139,31 @@ thread_local! {
     // There's a false-positive Clippy bug: <https://github.com/rust-lang/rust-clippy/issues/13422>
     #[cfg_attr(target_os = "android", allow(clippy::missing_const_for_thread_local))]
     static CURRENT_PROXY_ID: Cell<Option<i64>> = const { Cell::new(None) };
+    // Set by the panic hook for panics inside Rust handlers, then taken by `rust_proxy_handler`.
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+    static LAST_PROXY_PANIC: RefCell<Option<ProxyPanic>> = const { RefCell::new(None) };
+}
+
+/// Information of a panic caught in the Rust handler of a [DynamicProxy], which is converted
+/// to a `java.lang.RuntimeException` thrown to the Java caller. Check [DynamicProxy::take_last_panic].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProxyPanic {
+    pub proxy_id: i64,
+    pub message: String,
+    /// `file:line:column` of the panic; it is `None` unless the panic hook is installed by
+    /// [DynamicProxy::install_panic_location_hook] (and not replaced by `std::panic::set_hook`
+    /// afterwards).
+    pub location: Option<String>,
+}
+
+impl std::fmt::Display for ProxyPanic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rust panic in proxy {}: {}", self.proxy_id, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        Ok(())
+    }
 }
 
 /// Java dynamic proxy with an invocation handler backed by the Rust closure.
@@ -209,9 +251,46 @@ impl DynamicProxy {
         self.rust_hdl_id
     }
 
+    /// Creates a weak global reference of the Java proxy, which can be captured by the handler
+    /// of this proxy (set after building it, e.g. through a shared `OnceLock`).
+    ///
+    /// The handler is kept alive as long as the `DynamicProxy` (or forever after `forget()`);
+    /// if it captures a global reference of its own proxy, the Java object can never be garbage
+    /// collected. A weak reference avoids such a cycle, and can be upgraded when needed.
+    ///
+    /// ```
+    /// use jni::{jni_sig, jni_str, objects::JObject, refs::Weak};
+    /// use jni_min_helper::*;
+    /// use std::sync::{Arc, OnceLock};
+    ///
+    /// jni_init_vm_for_unit_test();
+    /// jni_with_env(|env| {
+    ///     let this: Arc<OnceLock<Weak<JObject<'static>>>> = Arc::new(OnceLock::new());
+    ///     let this_hdl = this.clone();
+    ///     let proxy = DynamicProxy::new_runnable(env, move |env| {
+    ///         let weak = this_hdl.get().unwrap();
+    ///         let this = weak.upgrade_local(env)?.unwrap();
+    ///         assert!(!this.is_null());
+    ///         Ok(())
+    ///     })?;
+    ///     let _ = this.set(proxy.proxy_weak(env)?);
+    ///     env.call_method(&proxy, jni_str!("run"), jni_sig!(() -> ()), &[])?;
+    ///     Ok::<_, jni::errors::Error>(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn proxy_weak(&self, env: &Env<'_>) -> Result<Weak<JObject<'static>>, Error> {
+        env.new_weak_ref(self.as_ref())
+    }
+
     /// Leaks the Rust handler and returns the global reference of the Java proxy.
     /// This is useful if the proxy is created for *once* in the program.
+    ///
+    /// The proxy is unregistered from [DynamicProxy::ret_self], which can't be used afterwards.
     pub fn forget(mut self) -> Global<JObject<'static>> {
+        if let Ok(mut objs_locked) = PROXY_OBJECTS.lock() {
+            let _ = objs_locked.remove(&self.rust_hdl_id);
+        }
         let obj = self.java_proxy.take().unwrap();
         forget(self);
         obj
@@ -223,6 +302,9 @@ impl Drop for DynamicProxy {
         if let Ok(mut hdls_locked) = RUST_HANDLERS.lock() {
             let _ = hdls_locked.remove(&self.rust_hdl_id);
         }
+        if let Ok(mut objs_locked) = PROXY_OBJECTS.lock() {
+            let _ = objs_locked.remove(&self.rust_hdl_id);
+        }
     }
 }
 
@@ -237,6 +319,7 @@ impl DynamicProxy {
     /// have to be wrapped.
     ///
     /// Returning an error in the Rust handler function causes a Java exception to be thrown.
+    /// A panic in it is caught and thrown as an exception too, check [DynamicProxy::take_last_panic].
     ///
     /// `equals()`, `hashCode()` and `toString()` are already implemented in the Java handler.
     pub fn build<'e, T, E, I, F>(
@@ -258,42 +341,423 @@ impl DynamicProxy {
             + Sync
             + 'static,
     {
-        let class_loader = match loader_context {
-            LoaderContext::Loader(loader) => env.new_local_ref(loader)?,
-            LoaderContext::FromObject(obj) => env.get_object_class(obj)?.get_class_loader(env)?,
-            LoaderContext::None => JClassLoader::get_system_class_loader(env)?,
-        };
-
-        // creates a Java class array for interfaces that should be supported
-        let interfaces = interfaces.into_iter();
-        let arr_interfaces =
-            env.new_object_type_array::<JClass>(interfaces.len(), JClass::null())?;
-        for (i, intr) in interfaces.enumerate() {
-            let intr = intr.lookup(env)?;
-            arr_interfaces.set_element(env, i, intr.as_ref())?;
-        }
+        let class_loader = resolve_class_loader(env, loader_context)?;
+        let arr_interfaces = new_interface_array(env, interfaces)?;
+        Self::new_instance(env, &class_loader, &arr_interfaces, Arc::new(handler))
+    }
 
-        // creates the proxy object with a new invocation handler, register the Rust handler with its ID
+    // Creates the proxy object with a new invocation handler, registers the Rust handler with its ID.
+    fn new_instance(
+        env: &mut Env<'_>,
+        class_loader: &JClassLoader<'_>,
+        arr_interfaces: &JObjectArray<JClass<'_>>,
+        handler: Arc<RustHandler>,
+    ) -> Result<Self, Error> {
         let mut handlers_locked = RUST_HANDLERS.lock().unwrap();
         let id: i64 = new_hdl_id(&handlers_locked);
         let invoc_hdl = InvocHdl::new(env, id)?;
-        let proxy = JProxy::new_proxy_instance(env, &class_loader, &arr_interfaces, &invoc_hdl)
+        let proxy = JProxy::new_proxy_instance(env, class_loader, arr_interfaces, &invoc_hdl)
             .inspect_err(|_| {
                 env.exception_describe();
             })?;
         let proxy = env.new_global_ref(proxy)?;
-        handlers_locked.insert(id, Arc::new(handler));
+        handlers_locked.insert(id, handler);
         Ok(Self {
             rust_hdl_id: id,
             java_proxy: Some(proxy),
         })
     }
 
+    /// Creates a Java dynamic proxy whose Rust handler returns a future; the calling Java thread
+    /// is *blocked* until the future resolves, then the resolved object is returned to the caller.
+    /// This is for integrating asynchronous Rust code with Java callers expecting a synchronous
+    /// return value. Check [DynamicProxy::build] for other parameters.
+    ///
+    /// The future is polled in the calling Java thread, which is parked while the future is
+    /// pending; it must be woken by something running in other threads. If `timeout` is given
+    /// and the future doesn't resolve in time, `java.util.concurrent.TimeoutException` is thrown
+    /// to the Java caller.
+    ///
+    /// Warning: Never let the Android main looper thread call such a proxy unless it is ensured
+    /// that the future resolves quickly, otherwise the application will be frozen and a fatal
+    /// "Application Not Responding" error may occur. The future must not depend on any event
+    /// processed in the calling thread, otherwise it will be a deadlock (or a timeout).
+    ///
+    /// ```
+    /// use jni::{jni_sig, jni_str, objects::*, refs::Global};
+    /// use jni_min_helper::*;
+    /// use std::time::Duration;
+    /// jni_init_vm_for_unit_test();
+    /// jni_with_env(|env| {
+    ///     let proxy = DynamicProxy::build_blocking_async(
+    ///         env,
+    ///         &LoaderContext::None,
+    ///         &[jni_str!("java.util.concurrent.Callable")],
+    ///         Some(Duration::from_secs(1)),
+    ///         |_, _, _| {
+    ///             let (tx, rx) = std::sync::mpsc::channel();
+    ///             std::thread::spawn(move || {
+    ///                 let _ = tx.send(jni_with_env(|env| {
+    ///                     let s = JString::new(env, "resolved")?;
+    ///                     env.new_global_ref(JObject::from(s))
+    ///                 }));
+    ///             });
+    ///             // this future is not a good example, but it is resolved in the other thread
+    ///             Ok(std::future::poll_fn(move |cx| {
+    ///                 cx.waker().wake_by_ref();
+    ///                 match rx.try_recv() {
+    ///                     Ok(result) => std::task::Poll::Ready(result),
+    ///                     Err(_) => std::task::Poll::Pending,
+    ///                 }
+    ///             }))
+    ///         },
+    ///     )?;
+    ///     let result = env
+    ///         .call_method(&proxy, jni_str!("call"), jni_sig!(() -> JObject), &[])?
+    ///         .l()
+    ///         .and_then(|l| JString::cast_local(env, l))?;
+    ///     assert_eq!(result.to_string(), "resolved");
+    ///
+    ///     // the future never resolves
+    ///     let proxy = DynamicProxy::build_blocking_async(
+    ///         env,
+    ///         &LoaderContext::None,
+    ///         &[jni_str!("java.util.concurrent.Callable")],
+    ///         Some(Duration::from_millis(10)),
+    ///         |_, _, _| Ok(std::future::pending::<Result<Global<JObject<'static>>, _>>()),
+    ///     )?;
+    ///     let result = env.call_method(&proxy, jni_str!("call"), jni_sig!(() -> JObject), &[]);
+    ///     assert!(matches!(result, Err(jni::errors::Error::JavaException)));
+    ///     let last_ex = env.exception_catch().unwrap_err();
+    ///     let jni::errors::Error::CaughtJavaException { name, .. } = last_ex else {
+    ///         unreachable!()
+    ///     };
+    ///     assert_eq!(name, "java.util.concurrent.TimeoutException");
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn build_blocking_async<'e, T, E, I, F, Fut>(
+        env: &mut jni::Env<'e>,
+        loader_context: &LoaderContext,
+        interfaces: I,
+        timeout: Option<Duration>,
+        handler: F,
+    ) -> Result<Self, Error>
+    where
+        T: Desc<'e, JClass<'e>>,
+        E: ExactSizeIterator<Item = T>,
+        I: IntoIterator<Item = T, IntoIter = E>,
+        F: for<'f> Fn(&mut Env<'f>, JMethod<'f>, JObjectArray<JObject<'f>>) -> Result<Fut, Error>
+            + Send
+            + Sync
+            + 'static,
+        Fut: Future<Output = Result<Global<JObject<'static>>, Error>>,
+    {
+        Self::build(env, loader_context, interfaces, move |env, method, args| {
+            let fut = handler(env, method, args)?;
+            match block_on_timeout(fut, timeout) {
+                Some(Ok(obj)) => env.new_local_ref(&obj),
+                Some(Err(e)) => Err(e),
+                None => {
+                    env.throw_new(
+                        jni_str!("java/util/concurrent/TimeoutException"),
+                        jni_str!("the Rust future of the proxy handler timed out"),
+                    )?;
+                    Err(Error::JavaException)
+                }
+            }
+        })
+    }
+
+    /// Creates a Java dynamic proxy like [DynamicProxy::build], with `error_mapper` deciding
+    /// the Java exception thrown for errors returned by `handler`. It returns the binary name
+    /// of a `Throwable` class having a constructor `(String)`, which is found by `FindClass`
+    /// (so it should be a JDK/Android framework class), and the message; for `None`, or
+    /// if an exception is already pending, the default behavior applies (a `RuntimeException`
+    /// is thrown with the error message). This helps to implement interfaces declaring checked
+    /// exceptions like `java.io.IOException`.
+    ///
+    /// ```
+    /// use jni::{errors::Error, jni_sig, jni_str, objects::*};
+    /// use jni_min_helper::*;
+    /// jni_init_vm_for_unit_test();
+    /// jni_with_env(|env| {
+    ///     let proxy = DynamicProxy::build_with_error_mapper(
+    ///         env,
+    ///         &LoaderContext::None,
+    ///         &[jni_str!("java.util.concurrent.Callable")],
+    ///         |e| match e {
+    ///             Error::FieldNotFound { name, .. } => {
+    ///                 Some(("java.io.FileNotFoundException", name.clone()))
+    ///             }
+    ///             _ => None,
+    ///         },
+    ///         |_, _, _| {
+    ///             Err(Error::FieldNotFound {
+    ///                 name: "config.json".into(),
+    ///                 sig: "file".into(),
+    ///             })
+    ///         },
+    ///     )?;
+    ///     let result = env.call_method(&proxy, jni_str!("call"), jni_sig!(() -> JObject), &[]);
+    ///     assert!(matches!(result, Err(Error::JavaException)));
+    ///     let Error::CaughtJavaException { name, msg, .. } = env.exception_catch().unwrap_err()
+    ///     else {
+    ///         unreachable!()
+    ///     };
+    ///     assert_eq!(name, "java.io.FileNotFoundException");
+    ///     assert_eq!(msg, "config.json");
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn build_with_error_mapper<'e, T, E, I, M, F>(
+        env: &mut jni::Env<'e>,
+        loader_context: &LoaderContext,
+        interfaces: I,
+        error_mapper: M,
+        handler: F,
+    ) -> Result<Self, Error>
+    where
+        T: Desc<'e, JClass<'e>>,
+        E: ExactSizeIterator<Item = T>,
+        I: IntoIterator<Item = T, IntoIter = E>,
+        M: Fn(&Error) -> Option<(&'static str, String)> + Send + Sync + 'static,
+        F: for<'f> Fn(
+                &mut Env<'f>,
+                JMethod<'f>,
+                JObjectArray<JObject<'f>>,
+            ) -> Result<JObject<'f>, Error>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self::build(env, loader_context, interfaces, move |env, method, args| {
+            let err = match handler(env, method, args) {
+                Ok(obj) => return Ok(obj),
+                Err(e) => e,
+            };
+            if env.exception_check() {
+                return Err(err);
+            }
+            let Some((class, msg)) = error_mapper(&err) else {
+                return Err(err);
+            };
+            let class = match env.find_class(JNIString::from(class.replace('.', "/"))) {
+                Ok(class) => class,
+                Err(e) => {
+                    env.exception_clear();
+                    warn!("DynamicProxy: exception class `{class}` is not found: {e}");
+                    return Err(err);
+                }
+            };
+            let _ = env.throw_new(&class, JNIString::from(msg));
+            Err(Error::JavaException)
+        })
+    }
+
+    /// Creates a `java.lang.Runnable` backed by the Rust closure, which is called in `run()`.
+    /// Returning an error in the closure causes a Java exception to be thrown.
+    ///
+    /// ```
+    /// use jni::{jni_sig, jni_str};
+    /// use jni_min_helper::*;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// jni_init_vm_for_unit_test();
+    /// static COUNT: AtomicUsize = AtomicUsize::new(0);
+    /// jni_with_env(|env| {
+    ///     let runnable = DynamicProxy::new_runnable(env, |_| {
+    ///         COUNT.fetch_add(1, Ordering::Relaxed);
+    ///         Ok(())
+    ///     })?;
+    ///     env.call_method(&runnable, jni_str!("run"), jni_sig!(() -> ()), &[])?;
+    ///     env.call_method(&runnable, jni_str!("run"), jni_sig!(() -> ()), &[])?;
+    ///     assert_eq!(COUNT.load(Ordering::Relaxed), 2);
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn new_runnable(
+        env: &mut Env<'_>,
+        runnable: impl Fn(&mut Env) -> Result<(), Error> + Send + Sync + 'static,
+    ) -> Result<Self, Error> {
+        Self::build(
+            env,
+            &LoaderContext::None,
+            [jni_str!("java/lang/Runnable")],
+            move |env, method, _| {
+                if &method.get_name(env)?.to_string() == "run" {
+                    runnable(env)?;
+                }
+                Ok(JObject::null())
+            },
+        )
+    }
+
+    /// Checks if `obj` is a Java proxy created by this crate (in the current library), and
+    /// returns its handler ID which can be compared with [DynamicProxy::id].
+    ///
+    /// ```
+    /// use jni::objects::JObject;
+    /// use jni_min_helper::*;
+    /// jni_init_vm_for_unit_test();
+    /// jni_with_env(|env| {
+    ///     let proxy = DynamicProxy::new_runnable(env, |_| Ok(()))?;
+    ///     assert_eq!(DynamicProxy::id_of(env, &proxy)?, Some(proxy.id()));
+    ///     let integer = JInteger::new(env, 1)?;
+    ///     assert_eq!(DynamicProxy::id_of(env, &integer)?, None);
+    ///     assert_eq!(DynamicProxy::id_of(env, &JObject::null())?, None);
+    ///     Ok::<_, jni::errors::Error>(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn id_of(env: &mut Env<'_>, obj: &JObject<'_>) -> Result<Option<i64>, Error> {
+        if obj.is_null() {
+            return Ok(None);
+        }
+        let class = env.get_object_class(obj)?;
+        if !JProxy::is_proxy_class(env, &class)? {
+            return Ok(None);
+        }
+        let invoc_hdl = JProxy::get_invocation_handler(env, obj)?;
+        let invoc_hdl_class = InvocHdl::lookup_class(env, &LoaderContext::None)?;
+        let invoc_hdl_class: &JClass = &invoc_hdl_class;
+        if !env.is_instance_of(&invoc_hdl, invoc_hdl_class)? {
+            return Ok(None);
+        }
+        let invoc_hdl = env.cast_local::<InvocHdl>(invoc_hdl)?;
+        invoc_hdl.get_id(env).map(Some)
+    }
+
+    /// Takes the information of the last panic caught in a Rust handler called in the current
+    /// thread. Such a panic doesn't unwind into the JVM; a `java.lang.RuntimeException` with
+    /// the same message as [ProxyPanic]'s `Display` form is thrown to the Java caller instead.
+    ///
+    /// ```
+    /// use jni::{errors::Error, jni_sig, jni_str, objects::*};
+    /// use jni_min_helper::*;
+    /// jni_init_vm_for_unit_test();
+    /// jni_with_env(|env| {
+    ///     DynamicProxy::install_panic_location_hook();
+    ///     let proxy = DynamicProxy::new_runnable(env, |_| panic!("handler failure"))?;
+    ///     let result = env.call_method(&proxy, jni_str!("run"), jni_sig!(() -> ()), &[]);
+    ///     assert!(matches!(result, Err(Error::JavaException)));
+    ///     let Error::CaughtJavaException { name, msg, .. } = env.exception_catch().unwrap_err()
+    ///     else {
+    ///         unreachable!()
+    ///     };
+    ///     assert_eq!(name, "java.lang.RuntimeException");
+    ///     let panic = DynamicProxy::take_last_panic().unwrap();
+    ///     assert_eq!(panic.proxy_id, proxy.id());
+    ///     assert_eq!(panic.message, "handler failure");
+    ///     assert!(panic.location.as_ref().unwrap().contains(".rs:"));
+    ///     assert_eq!(msg, panic.to_string());
+    ///     assert!(DynamicProxy::take_last_panic().is_none());
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn take_last_panic() -> Option<ProxyPanic> {
+        LAST_PROXY_PANIC.take()
+    }
+
+    /// Chains a panic hook (installed only once) recording the location of panics inside Rust
+    /// handlers, which fills [ProxyPanic]'s `location`; other panics are passed to the previous
+    /// hook as before. Call it at startup if needed, before any custom hook set by
+    /// `std::panic::set_hook` which doesn't call the previous one.
+    pub fn install_panic_location_hook() {
+        static INSTALLED: Once = Once::new();
+        INSTALLED.call_once(|| {
+            let prev_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                if CURRENT_PROXY_ID.get().is_some() {
+                    let location = info.location().map(|l| l.to_string());
+                    PANIC_LOCATION.with_borrow_mut(|loc| *loc = location);
+                }
+                prev_hook(info)
+            }));
+        });
+    }
+
     /// Gets the invoked proxy ID inside the Rust handler closure for debugging;
     /// returns `None` elsewhere.
     pub fn current_proxy_id() -> Option<i64> {
         CURRENT_PROXY_ID.get()
     }
+
+    /// Gets a new local reference of the invoked proxy object inside the Rust handler closure,
+    /// which can be returned by methods of fluent builder interfaces returning `this`.
+    /// Returns `Error::NullPtr` if it is called outside of a handler, or if the proxy is not
+    /// registered by [DynamicProxy::enable_ret_self].
+    ///
+    /// The proxy object is found through a weak global reference kept until the `DynamicProxy`
+    /// is dropped, so the handler doesn't need to capture a reference of its own proxy
+    /// (check [DynamicProxy::proxy_weak]). The returned local reference is freed by the JVM
+    /// after it is returned to the Java caller.
+    ///
+    /// ```
+    /// use jni::{jni_sig, jni_str, objects::*};
+    /// use jni_min_helper::*;
+    /// use std::sync::{Arc, Mutex};
+    /// jni_init_vm_for_unit_test();
+    /// jni_with_env(|env| {
+    ///     let text = Arc::new(Mutex::new(String::new()));
+    ///     let text_hdl = text.clone();
+    ///     let proxy = DynamicProxy::build(
+    ///         env,
+    ///         &LoaderContext::None,
+    ///         &[jni_str!("java.lang.Appendable")],
+    ///         move |env, _, args| {
+    ///             let s = args.get_element(env, 0)?;
+    ///             text_hdl.lock().unwrap().push_str(&env.as_cast::<JString>(&s)?.to_string());
+    ///             DynamicProxy::ret_self(env)
+    ///         },
+    ///     )?;
+    ///     proxy.enable_ret_self(env)?;
+    ///     let mut this = env.new_local_ref(&*proxy)?;
+    ///     for s in ["fluent ", "builder"] {
+    ///         let s = JString::new(env, s)?;
+    ///         this = env
+    ///             .call_method(
+    ///                 &this,
+    ///                 jni_str!("append"),
+    ///                 jni_sig!((java.lang.CharSequence) -> java.lang.Appendable),
+    ///                 &[(&s).into()],
+    ///             )?
+    ///             .l()?;
+    ///         assert!(env.is_same_object(&this, &*proxy)?);
+    ///     }
+    ///     assert_eq!(*text.lock().unwrap(), "fluent builder");
+    ///     assert!(DynamicProxy::ret_self(env).is_err());
+    ///     Ok::<_, jni::errors::Error>(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn ret_self<'local>(env: &mut Env<'local>) -> Result<JObject<'local>, Error> {
+        let id = CURRENT_PROXY_ID.get().ok_or(Error::NullPtr(
+            "`ret_self` is called outside of a proxy handler",
+        ))?;
+        let objs_locked = PROXY_OBJECTS.lock().unwrap();
+        let proxy_weak = objs_locked.get(&id).ok_or(Error::NullPtr(
+            "the proxy is dropped or not registered by `enable_ret_self`",
+        ))?;
+        proxy_weak.upgrade_local(env)?.ok_or(Error::NullPtr(
+            "the proxy object has been garbage collected",
+        ))
+    }
+
+    /// Registers a weak global reference of the proxy object for [DynamicProxy::ret_self].
+    /// Call it before the proxy is passed to Java code which may call it. The registration
+    /// is removed when the proxy is dropped (or returned to the pool), or on `forget()`.
+    pub fn enable_ret_self(&self, env: &Env<'_>) -> Result<(), Error> {
+        let proxy_weak = self.proxy_weak(env)?;
+        PROXY_OBJECTS
+            .lock()
+            .unwrap()
+            .insert(self.rust_hdl_id, proxy_weak);
+        Ok(())
+    }
 }
 
 #[cfg(target_os = "android")]
@@ -307,23 +771,6 @@ impl DynamicProxy {
         runnable: impl Fn(&mut jni::Env) -> Result<(), Error> + Send + Sync + 'static,
     ) -> Result<bool, Error> {
         crate::jni_with_env(|env| {
-            let runnable = DynamicProxy::build(
-                env,
-                &LoaderContext::None,
-                [jni_str!("java/lang/Runnable")],
-                move |env, method, _| {
-                    if &method.get_name(env)?.to_string() == "run" {
-                        let _ = runnable(env);
-                        env.exception_clear();
-                    }
-                    if let (Some(cur_id), Ok(mut hdls_locked)) =
-                        (DynamicProxy::current_proxy_id(), RUST_HANDLERS.lock())
-                    {
-                        let _ = hdls_locked.remove(&cur_id);
-                    }
-                    Ok(JObject::null())
-                },
-            )?;
             let main_looper = AndroidLooper::get_main_looper(env)?;
             if main_looper.is_null() {
                 return Err(Error::NullPtr(
@@ -331,16 +778,285 @@ impl DynamicProxy {
                 ));
             }
             let handler = AndroidHandler::new(env, main_looper)?;
-            let new_runnable_ref = env.new_local_ref(runnable.as_ref())?;
-            let casted_runnable = JRunnable::cast_local(env, new_runnable_ref)?;
-            let is_posted = handler.post(env, casted_runnable)?;
-            if is_posted {
-                // the runnable will remove the handler by itself, when it is called for once
-                let _ = runnable.forget();
+            Self::post_to_handler(env, &handler, runnable)
+        })
+    }
+
+    /// Works like [DynamicProxy::build], for callbacks that must run on the main (UI) thread.
+    ///
+    /// In debug builds, it checks `Looper.myLooper() == Looper.getMainLooper()` before each
+    /// invocation of the handler and logs a warning with the method name on mismatch; the
+    /// handler is still called. In release builds it is the same as [DynamicProxy::build].
+    pub fn build_expect_main_thread<'e, T, E, I, F>(
+        env: &mut jni::Env<'e>,
+        loader_context: &LoaderContext,
+        interfaces: I,
+        handler: F,
+    ) -> Result<Self, Error>
+    where
+        T: Desc<'e, JClass<'e>>,
+        E: ExactSizeIterator<Item = T>,
+        I: IntoIterator<Item = T, IntoIter = E>,
+        F: for<'f> Fn(
+                &mut Env<'f>,
+                JMethod<'f>,
+                JObjectArray<JObject<'f>>,
+            ) -> Result<JObject<'f>, Error>
+            + Send
+            + Sync
+            + 'static,
+    {
+        if !cfg!(debug_assertions) {
+            return Self::build(env, loader_context, interfaces, handler);
+        }
+        Self::build(env, loader_context, interfaces, move |env, method, args| {
+            let is_main = env
+                .with_local_frame(2, |env| -> Result<bool, Error> {
+                    let my_looper = AndroidLooper::my_looper(env)?;
+                    let main_looper = AndroidLooper::get_main_looper(env)?;
+                    Ok(!my_looper.is_null() && env.is_same_object(&my_looper, &main_looper)?)
+                })
+                .unwrap_or(true); // don't disturb the handler if the check itself fails
+            if !is_main {
+                let name = method.get_name(env).map(|n| n.to_string());
+                warn!(
+                    "DynamicProxy: method `{}` is expected to run on the main thread, called on `{}`",
+                    name.as_deref().unwrap_or("?"),
+                    thread::current().name().unwrap_or("unnamed thread")
+                );
             }
-            Ok(is_posted)
+            handler(env, method, args)
         })
     }
+
+    // Posts the runnable, which removes its own handler when it is called for once.
+    pub(crate) fn post_to_handler(
+        env: &mut Env<'_>,
+        handler: &AndroidHandler<'_>,
+        runnable: impl Fn(&mut jni::Env) -> Result<(), Error> + Send + Sync + 'static,
+    ) -> Result<bool, Error> {
+        let runnable = DynamicProxy::new_runnable(env, move |env| {
+            let _ = runnable(env);
+            env.exception_clear();
+            if let (Some(cur_id), Ok(mut hdls_locked)) =
+                (DynamicProxy::current_proxy_id(), RUST_HANDLERS.lock())
+            {
+                let _ = hdls_locked.remove(&cur_id);
+                drop(hdls_locked);
+                let _ = PROXY_OBJECTS.lock().unwrap().remove(&cur_id);
+            }
+            Ok(())
+        })?;
+        let new_runnable_ref = env.new_local_ref(runnable.as_ref())?;
+        let casted_runnable = JRunnable::cast_local(env, new_runnable_ref)?;
+        let is_posted = handler.post(env, casted_runnable)?;
+        if is_posted {
+            // the runnable will remove the handler by itself, when it is called for once
+            let _ = runnable.forget();
+        }
+        Ok(is_posted)
+    }
+}
+
+/// Pool of Java dynamic proxies implementing the same interfaces, which reuses idle proxy
+/// objects and only swaps the Rust handler on each acquisition. This amortizes the cost of
+/// creating invocation handlers and proxy instances for many short-lived callbacks.
+///
+/// The proxy object is returned to the pool when the [PooledProxy] is dropped, so the Java side
+/// must not keep it beyond that; otherwise it would call the handler of the next acquisition.
+///
+/// ```
+/// use jni::{jni_sig, jni_str, objects::LoaderContext};
+/// use jni_min_helper::*;
+/// use std::sync::{Arc, atomic::{AtomicU32, Ordering}};
+/// jni_init_vm_for_unit_test();
+/// jni_with_env(|env| {
+///     let pool = DynamicProxyPool::new(env, &LoaderContext::None, &[jni_str!("java.lang.Runnable")])?;
+///     let count = Arc::new(AtomicU32::new(0));
+///     let count_hdl = count.clone();
+///     let proxy = pool.acquire(env, move |_, _, _| {
+///         count_hdl.fetch_add(1, Ordering::Relaxed);
+///         Ok(jni::objects::JObject::null())
+///     })?;
+///     env.call_method(&*proxy, jni_str!("run"), jni_sig!(() -> ()), &[])?;
+///     let id = proxy.id();
+///     drop(proxy);
+///     assert_eq!(pool.count_idle(), 1);
+///
+///     let count_hdl = count.clone();
+///     let proxy = pool.acquire(env, move |_, _, _| {
+///         count_hdl.fetch_add(10, Ordering::Relaxed);
+///         Ok(jni::objects::JObject::null())
+///     })?;
+///     assert_eq!(proxy.id(), id);
+///     assert_eq!(pool.count_idle(), 0);
+///     env.call_method(&*proxy, jni_str!("run"), jni_sig!(() -> ()), &[])?;
+///     assert_eq!(count.load(Ordering::Relaxed), 11);
+///     Ok::<_, jni::errors::Error>(())
+/// })
+/// .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct DynamicProxyPool {
+    class_loader: Global<JClassLoader<'static>>,
+    interfaces: Global<JObjectArray<'static, JClass<'static>>>,
+    idle: Arc<Mutex<Vec<DynamicProxy>>>,
+}
+
+impl DynamicProxyPool {
+    /// Creates an empty pool. Check [DynamicProxy::build] for parameters.
+    pub fn new<'e, T, E, I>(
+        env: &mut jni::Env<'e>,
+        loader_context: &LoaderContext,
+        interfaces: I,
+    ) -> Result<Self, Error>
+    where
+        T: Desc<'e, JClass<'e>>,
+        E: ExactSizeIterator<Item = T>,
+        I: IntoIterator<Item = T, IntoIter = E>,
+    {
+        let class_loader = resolve_class_loader(env, loader_context)?;
+        let interfaces = new_interface_array(env, interfaces)?;
+        Ok(Self {
+            class_loader: env.new_global_ref(class_loader)?,
+            interfaces: env.new_global_ref(interfaces)?,
+            idle: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    /// Takes an idle proxy (or creates a new one) and sets its Rust handler.
+    pub fn acquire<F>(&self, env: &mut Env<'_>, handler: F) -> Result<PooledProxy, Error>
+    where
+        F: for<'f> Fn(
+                &mut Env<'f>,
+                JMethod<'f>,
+                JObjectArray<JObject<'f>>,
+            ) -> Result<JObject<'f>, Error>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let idle = self.idle.lock().unwrap().pop();
+        let proxy = if let Some(proxy) = idle {
+            RUST_HANDLERS
+                .lock()
+                .unwrap()
+                .insert(proxy.rust_hdl_id, Arc::new(handler));
+            proxy
+        } else {
+            DynamicProxy::new_instance(
+                env,
+                &self.class_loader,
+                &self.interfaces,
+                Arc::new(handler),
+            )?
+        };
+        Ok(PooledProxy {
+            proxy: Some(proxy),
+            idle: Arc::downgrade(&self.idle),
+        })
+    }
+
+    /// Returns the amount of idle proxies kept in the pool.
+    pub fn count_idle(&self) -> usize {
+        self.idle.lock().unwrap().len()
+    }
+}
+
+/// Proxy acquired from [DynamicProxyPool]. Its Rust handler is removed on dropping, then
+/// the proxy object is returned to the pool (if the pool still exists).
+#[derive(Debug)]
+pub struct PooledProxy {
+    proxy: Option<DynamicProxy>, // always `Some` before `drop`
+    idle: std::sync::Weak<Mutex<Vec<DynamicProxy>>>,
+}
+
+impl std::ops::Deref for PooledProxy {
+    type Target = DynamicProxy;
+    fn deref(&self) -> &Self::Target {
+        self.proxy.as_ref().unwrap()
+    }
+}
+
+impl AsRef<JObject<'static>> for PooledProxy {
+    fn as_ref(&self) -> &JObject<'static> {
+        self.proxy.as_ref().unwrap().as_ref()
+    }
+}
+
+impl Drop for PooledProxy {
+    fn drop(&mut self) {
+        let proxy = self.proxy.take().unwrap();
+        if let Ok(mut hdls_locked) = RUST_HANDLERS.lock() {
+            let _ = hdls_locked.remove(&proxy.rust_hdl_id);
+        }
+        if let Ok(mut objs_locked) = PROXY_OBJECTS.lock() {
+            let _ = objs_locked.remove(&proxy.rust_hdl_id);
+        }
+        if let Some(idle) = self.idle.upgrade() {
+            idle.lock().unwrap().push(proxy);
+        }
+    }
+}
+
+// Minimal executor for `DynamicProxy::build_blocking_async` (and `block_with_timeout` on Android),
+// it parks the current thread until the future is woken. Returns `None` on timeout.
+pub(crate) fn block_on_timeout<F: Future>(fut: F, timeout: Option<Duration>) -> Option<F::Output> {
+    struct ThreadWaker(Thread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let deadline = timeout.map(|dur| Instant::now() + dur);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(fut);
+    loop {
+        if let Poll::Ready(output) = fut.as_mut().poll(&mut cx) {
+            return Some(output);
+        }
+        if let Some(deadline) = deadline {
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+            thread::park_timeout(deadline - now);
+        } else {
+            thread::park();
+        }
+    }
+}
+
+fn resolve_class_loader<'e>(
+    env: &mut Env<'e>,
+    loader_context: &LoaderContext,
+) -> Result<JClassLoader<'e>, Error> {
+    match loader_context {
+        LoaderContext::Loader(loader) => env.new_local_ref(loader),
+        LoaderContext::FromObject(obj) => env.get_object_class(obj)?.get_class_loader(env),
+        LoaderContext::None => JClassLoader::get_system_class_loader(env),
+    }
+}
+
+// Creates a Java class array for interfaces that should be supported.
+fn new_interface_array<'e, T, E, I>(
+    env: &mut Env<'e>,
+    interfaces: I,
+) -> Result<JObjectArray<'e, JClass<'e>>, Error>
+where
+    T: Desc<'e, JClass<'e>>,
+    E: ExactSizeIterator<Item = T>,
+    I: IntoIterator<Item = T, IntoIter = E>,
+{
+    let interfaces = interfaces.into_iter();
+    let arr_interfaces = env.new_object_type_array::<JClass>(interfaces.len(), JClass::null())?;
+    for (i, intr) in interfaces.enumerate() {
+        let intr = intr.lookup(env)?;
+        arr_interfaces.set_element(env, i, intr.as_ref())?;
+    }
+    Ok(arr_interfaces)
 }
 
 // Note: this function depends on `clock_gettime()` on UNIX, including Android.
@@ -378,8 +1094,71 @@ fn rust_proxy_handler<'local>(
     };
     // ReentrantMutex is not needed(?) even if `rust_hdl()` registers another handler.
     drop(lock);
-    CURRENT_PROXY_ID.replace(Some(id));
-    let result = rust_hdl(env, method, args);
-    let _ = CURRENT_PROXY_ID.take();
-    result
+    let prev_id = CURRENT_PROXY_ID.replace(Some(id));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| rust_hdl(env, method, args)));
+    CURRENT_PROXY_ID.set(prev_id);
+    match result {
+        Ok(result) => result,
+        Err(payload) => {
+            let panic = ProxyPanic {
+                proxy_id: id,
+                message: crate::panic_message(&*payload).to_string(),
+                location: PANIC_LOCATION.take(),
+            };
+            let msg = panic.to_string();
+            LAST_PROXY_PANIC.set(Some(panic));
+            // an exception thrown before the panic is replaced
+            env.exception_clear();
+            let _ = env.throw_new(jni_str!("java/lang/RuntimeException"), JNIString::from(msg));
+            Err(Error::JavaException)
+        }
+    }
+}
+
+#[test]
+#[cfg(not(target_os = "android"))]
+fn verify_private_helper_loader() {
+    use crate::{jni_init_vm_for_unit_test, jni_with_env};
+    use jni::objects::JString;
+    jni_init_vm_for_unit_test();
+    jni_with_env(|env| {
+        let proxy = DynamicProxy::new_runnable(env, |_| Ok(()))?;
+        let invoc_hdl = InvocHdl::lookup_class(env, &LoaderContext::None)?;
+        let invoc_hdl: &JClass = &invoc_hdl;
+        let loader = invoc_hdl.get_class_loader(env)?;
+        let system_loader = JClassLoader::get_system_class_loader(env)?;
+        assert!(!env.is_same_object(&loader, &system_loader)?);
+        // not visible to other libraries loading classes from the system class loader
+        let name = JString::new(env, "rust.jniminhelper.InvocHdl")?;
+        assert!(system_loader.load_class(env, name).is_err());
+        env.exception_clear();
+        drop(proxy);
+        Ok::<_, Error>(())
+    })
+    .unwrap();
+}
+
+#[test]
+#[cfg(not(target_os = "android"))]
+fn verify_ret_self_registration() {
+    use crate::{jni_init_vm_for_unit_test, jni_with_env};
+    jni_init_vm_for_unit_test();
+    jni_with_env(|env| {
+        let is_registered = |id| PROXY_OBJECTS.lock().unwrap().contains_key(&id);
+        let proxy = DynamicProxy::new_runnable(env, |_| Ok(()))?;
+        let id = proxy.id();
+        assert!(!is_registered(id));
+        proxy.enable_ret_self(env)?;
+        assert!(is_registered(id));
+        drop(proxy);
+        assert!(!is_registered(id));
+
+        let proxy = DynamicProxy::new_runnable(env, |_| Ok(()))?;
+        let id = proxy.id();
+        proxy.enable_ret_self(env)?;
+        let _ = proxy.forget();
+        assert!(!is_registered(id));
+        Ok::<_, Error>(())
+    })
+    .unwrap();
 }
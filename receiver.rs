@@ -1,30 +1,27 @@
-use crate::{
-    android::{AndroidContext, get_android_context, get_helper_class_loader},
-    jni_with_env,
-    proxy::DynamicProxy,
-};
-
-use jni::{
-    Env,
-    errors::Error,
-    objects::{JClass, JObject, JString},
-    refs::{Global, Reference},
-};
+use crate::{android::AndroidContext, bundle::AndroidBundle, uri::AndroidUri};
+
+use jni::refs::Reference;
 
 jni::bind_java_type! {
     pub Intent => "android.content.Intent",
     type_map = {
         AndroidContext => "android.content.Context",
         AndroidParcelable => "android.os.Parcelable",
+        AndroidUri => "android.net.Uri",
+        AndroidBundle => "android.os.Bundle",
     },
     constructors {
         fn new(),
         fn new_with_action(action: JString),
+        fn new_with_action_uri {
+            sig = (action: JString, uri: AndroidUri),
+        },
     },
     methods {
         fn get_package() -> JString,
         fn get_type() -> JString,
         fn get_action() -> JString,
+        fn get_data() -> AndroidUri,
         fn has_extra(name: JString) -> jboolean,
         fn get_string_extra(name: JString) -> JString,
         fn get_int_extra(name: JString, default_value: jint) -> jint,
@@ -36,7 +33,15 @@ jni::bind_java_type! {
         fn get_char_extra(name: JString, default_value: jchar) -> jchar,
         fn get_boolean_extra(name: JString, default_value: jboolean) -> jboolean,
         fn get_byte_array_extra(name: JString) -> jbyte[],
+        fn get_int_array_extra(name: JString) -> jint[],
+        fn get_string_array_extra(name: JString) -> JString[],
         fn set_action(action: JString) -> Intent,
+        fn set_type(r#type: JString) -> Intent,
+        fn add_flags(flags: jint) -> Intent,
+        fn put_extras {
+            sig = (extras: AndroidBundle) -> Intent,
+        },
+        static fn create_chooser(target: Intent, title: JCharSequence) -> Intent,
         fn set_class(package_context: AndroidContext, cls: JClass) -> Intent,
         fn put_extra_bool {
             name = "putExtra",
@@ -95,7 +100,7 @@ jni::bind_java_type! {
 
 mod parcelable_extra {
     use super::{AndroidParcelable, Intent};
-    use crate::android_api_level;
+    use crate::{AndroidFeature, android_feature_available};
     use jni::{
         Env,
         errors::Error,
@@ -154,7 +159,7 @@ mod parcelable_extra {
                     "null `class` provided for `get_parcelable_extra`",
                 ));
             }
-            if android_api_level() >= 33 {
+            if android_feature_available(AndroidFeature::TypedParcelableExtra) {
                 // Safety: `Intent` and `IntentApi33` both map to `android.content.Intent`.
                 let intent: Cast<'_, '_, IntentApi33> = unsafe { env.as_cast_unchecked(self) };
                 intent.get_parcelable_extra(env, name, class)
@@ -188,278 +193,399 @@ jni::bind_java_type! {
     pub(crate) AndroidBroadcastReceiver => "android.content.BroadcastReceiver",
 }
 
-jni::bind_java_type! {
-    BroadcastRec => "rust.jniminhelper.BroadcastRec",
-    type_map = {
-        BroadcastRecHdl => "rust.jniminhelper.BroadcastRec$BroadcastRecHdl",
-        AndroidBroadcastReceiver => "android.content.BroadcastReceiver",
-    },
-    constructors {
-        fn new(hdl: BroadcastRecHdl),
-    },
-    is_instance_of = {
-        AndroidBroadcastReceiver,
-    }
-}
+#[cfg(feature = "broadcast")]
+pub use broadcast::*;
 
-jni::bind_java_type! {
-    BroadcastRecHdl => "rust.jniminhelper.BroadcastRec$BroadcastRecHdl",
-}
+#[cfg(feature = "broadcast")]
+mod broadcast {
+    use super::*;
+    use crate::{
+        BoundAction, BoundStatus,
+        android::{android_helper_class_loader, get_android_context},
+        jni_with_env,
+        lifecycle::LifecycleWatcher,
+        proxy::DynamicProxy,
+    };
+    use jni::{
+        Env,
+        errors::Error,
+        objects::{JClass, JObject, JString},
+        refs::Global,
+    };
+    use std::{
+        marker::PhantomData,
+        sync::{Arc, Mutex},
+    };
 
-/// Handles `android.content.BroadcastReceiver` object backed by `JniProxy`.
-///
-/// Register/unregister functions are provided for convenience, but not for
-/// maintaining any internal state. However, `unregister()` is called on `drop()`.
-#[derive(Debug)]
-pub struct BroadcastReceiver {
-    receiver: Global<AndroidBroadcastReceiver<'static>>,
-    proxy: Option<DynamicProxy>, // taken on `forget()`
-    forget: bool,
-}
+    jni::bind_java_type! {
+        BroadcastRec => "rust.jniminhelper.BroadcastRec",
+        type_map = {
+            BroadcastRecHdl => "rust.jniminhelper.BroadcastRec$BroadcastRecHdl",
+            AndroidBroadcastReceiver => "android.content.BroadcastReceiver",
+        },
+        constructors {
+            fn new(hdl: BroadcastRecHdl),
+        },
+        is_instance_of = {
+            AndroidBroadcastReceiver,
+        }
+    }
 
-impl AsRef<JObject<'static>> for BroadcastReceiver {
-    fn as_ref(&self) -> &JObject<'static> {
-        self.receiver.as_obj()
+    jni::bind_java_type! {
+        BroadcastRecHdl => "rust.jniminhelper.BroadcastRec$BroadcastRecHdl",
     }
-}
 
-impl std::ops::Deref for BroadcastReceiver {
-    type Target = JObject<'static>;
-    fn deref(&self) -> &Self::Target {
-        self.receiver.as_obj()
+    /// Handles `android.content.BroadcastReceiver` object backed by `JniProxy`.
+    ///
+    /// Register/unregister functions are provided for convenience, but not for
+    /// maintaining any internal state. However, `unregister()` is called on `drop()`.
+    #[derive(Debug)]
+    pub struct BroadcastReceiver {
+        receiver: Global<AndroidBroadcastReceiver<'static>>,
+        proxy: Option<DynamicProxy>, // taken on `forget()`
+        forget: bool,
     }
-}
 
-impl Drop for BroadcastReceiver {
-    fn drop(&mut self) {
-        if !self.forget {
-            let _ = self.unregister();
+    impl AsRef<JObject<'static>> for BroadcastReceiver {
+        fn as_ref(&self) -> &JObject<'static> {
+            self.receiver.as_obj()
         }
     }
-}
 
-impl BroadcastReceiver {
-    /// Creates a `android.content.BroadcastReceiver` object backed by the Rust closure.
-    ///
-    /// The two Java object references passed to the closure are `context` and `intent`.
-    ///
-    /// Note: without a Rust panic, no exception may be thrown from `onReceive()`.
-    pub fn build(
-        handler: impl for<'a> Fn(&mut Env<'a>, JObject<'a>, Intent<'a>) -> Result<(), Error>
-        + Send
-        + Sync
-        + 'static,
-    ) -> Result<Self, Error> {
-        jni_with_env(|env| {
-            let loader = &jni::refs::LoaderContext::Loader(get_helper_class_loader()?);
-            let _ = BroadcastRecHdlAPI::get(env, loader)?;
-            let _ = BroadcastRecAPI::get(env, loader)?;
-            let cls_rec_hdl = BroadcastRecHdl::lookup_class(env, loader)?;
-            use std::ops::Deref;
-            let proxy = DynamicProxy::build(
-                env,
-                loader,
-                [AsRef::<JClass>::as_ref(&cls_rec_hdl.deref())],
-                move |env, method, args| {
-                    if &method.get_name(env)?.to_string() == "onReceive" && args.len(env)? == 2 {
-                        let context = args.get_element(env, 0)?;
-                        let intent = args.get_element(env, 1)?;
-                        let intent = Intent::cast_local(env, intent)?;
-                        let _ = handler(env, context, intent);
-                        env.exception_clear();
-                    }
-                    Ok(JObject::null())
-                },
-            )?;
-
-            let receiver_hdl = env.new_local_ref(proxy.as_ref())?;
-            let receiver_hdl = env.cast_local::<BroadcastRecHdl>(receiver_hdl)?;
-            let receiver = BroadcastRec::new(env, receiver_hdl)?;
-
-            Ok(Self {
-                receiver: env.new_global_ref(AndroidBroadcastReceiver::from(receiver))?,
-                proxy: Some(proxy),
-                forget: false,
-            })
-        })
+    impl std::ops::Deref for BroadcastReceiver {
+        type Target = JObject<'static>;
+        fn deref(&self) -> &Self::Target {
+            self.receiver.as_obj()
+        }
     }
 
-    /// Registers the receiver to the current Android context.
-    pub fn register(&self, intent_filter: &IntentFilter<'_>) -> Result<(), Error> {
-        jni_with_env(|env| {
-            let context = get_android_context();
-            context.register_receiver(env, &self.receiver, intent_filter)?;
-            Ok(())
-        })
+    impl Drop for BroadcastReceiver {
+        fn drop(&mut self) {
+            if !self.forget {
+                let _ = self.unregister();
+            }
+        }
     }
 
-    /// Registers the receiver to the current Android context, with an intent filter
-    /// that matches a single `action` with no data.
-    pub fn register_for_action(&self, action: &str) -> Result<(), Error> {
-        jni_with_env(|env| {
-            let action = JString::new(env, action)?;
-            let filter = IntentFilter::new_with_action(env, action)?;
-            self.register(&filter)
-        })
-    }
+    impl BroadcastReceiver {
+        /// Creates a `android.content.BroadcastReceiver` object backed by the Rust closure.
+        ///
+        /// The two Java object references passed to the closure are `context` and `intent`.
+        ///
+        /// Note: without a Rust panic, no exception may be thrown from `onReceive()`.
+        pub fn build(
+            handler: impl for<'a> Fn(&mut Env<'a>, JObject<'a>, Intent<'a>) -> Result<(), Error>
+            + Send
+            + Sync
+            + 'static,
+        ) -> Result<Self, Error> {
+            crate::check_helper_class("BroadcastRec")?;
+            jni_with_env(|env| {
+                let loader = &jni::refs::LoaderContext::Loader(android_helper_class_loader()?);
+                let _ = BroadcastRecHdlAPI::get(env, loader)?;
+                let _ = BroadcastRecAPI::get(env, loader)?;
+                let cls_rec_hdl = BroadcastRecHdl::lookup_class(env, loader)?;
+                use std::ops::Deref;
+                let proxy = DynamicProxy::build(
+                    env,
+                    loader,
+                    [AsRef::<JClass>::as_ref(&cls_rec_hdl.deref())],
+                    move |env, method, args| {
+                        if &method.get_name(env)?.to_string() == "onReceive" && args.len(env)? == 2
+                        {
+                            let context = args.get_element(env, 0)?;
+                            let intent = args.get_element(env, 1)?;
+                            let intent = Intent::cast_local(env, intent)?;
+                            let _ = handler(env, context, intent);
+                            env.exception_clear();
+                        }
+                        Ok(JObject::null())
+                    },
+                )?;
+
+                let receiver_hdl = env.new_local_ref(proxy.as_ref())?;
+                let receiver_hdl = env.cast_local::<BroadcastRecHdl>(receiver_hdl)?;
+                let receiver = BroadcastRec::new(env, receiver_hdl)?;
+
+                Ok(Self {
+                    receiver: env.new_global_ref(AndroidBroadcastReceiver::from(receiver))?,
+                    proxy: Some(proxy),
+                    forget: false,
+                })
+            })
+        }
 
-    /// Unregister the previously registered broadcast receiver. All filters that have been
-    /// registered for this receiver will be removed.
-    #[inline(always)]
-    pub fn unregister(&self) -> Result<(), Error> {
-        jni_with_env(|env| {
-            let context = get_android_context();
-            context.unregister_receiver(env, &self.receiver).map(|_| ())
-        })
-    }
+        /// Registers the receiver to the current Android context.
+        pub fn register(&self, intent_filter: &IntentFilter<'_>) -> Result<(), Error> {
+            jni_with_env(|env| {
+                let context = get_android_context();
+                context.register_receiver(env, &self.receiver, intent_filter)?;
+                Ok(())
+            })
+        }
 
-    /// Leaks the Rust handler and returns the global reference of the broadcast
-    /// receiver. It prevents deregistering of the receiver on dropping. This is
-    /// useful if it is created for *once* in the program.
-    pub fn forget(mut self) -> Global<JObject<'static>> {
-        self.forget = true;
-        self.proxy.take().unwrap().forget();
-        jni_with_env(|env| env.new_cast_global_ref::<JObject>(&self.receiver)).unwrap()
-    }
-}
+        /// Registers the receiver like [BroadcastReceiver::register], and returns the last sticky
+        /// broadcast matching the filter (e.g. `android.intent.action.BATTERY_CHANGED`), or `None`
+        /// if there is no such intent. This allows reading the current state immediately.
+        pub fn register_get_sticky(
+            &self,
+            intent_filter: &IntentFilter<'_>,
+        ) -> Result<Option<Global<Intent<'static>>>, Error> {
+            jni_with_env(|env| {
+                let context = get_android_context();
+                let sticky = context.register_receiver(env, &self.receiver, intent_filter)?;
+                if sticky.is_null() {
+                    return Ok(None);
+                }
+                Ok(Some(env.new_global_ref(sticky)?))
+            })
+        }
 
-#[cfg(feature = "futures")]
-pub use waiter::*;
+        /// Registers the receiver to the current Android context, with an intent filter
+        /// that matches a single `action` with no data.
+        pub fn register_for_action(&self, action: &str) -> Result<(), Error> {
+            jni_with_env(|env| {
+                let action = JString::new(env, action)?;
+                let filter = IntentFilter::new_with_action(env, action)?;
+                self.register(&filter)
+            })
+        }
 
-#[cfg(feature = "futures")]
-mod waiter {
-    use super::*;
-    use futures_lite::StreamExt;
-    use std::{
-        collections::VecDeque,
-        pin::Pin,
-        sync::{Arc, Mutex},
-        task,
-        time::Duration,
-    };
+        /// Unregister the previously registered broadcast receiver. All filters that have been
+        /// registered for this receiver will be removed.
+        #[inline(always)]
+        pub fn unregister(&self) -> Result<(), Error> {
+            jni_with_env(|env| {
+                let context = get_android_context();
+                context.unregister_receiver(env, &self.receiver).map(|_| ())
+            })
+        }
 
-    /// Waits for intents received by the managed `BroadcastReceiver`.
-    #[derive(Debug)]
-    pub struct BroadcastWaiter {
-        receiver: BroadcastReceiver,
-        inner: Arc<BroadcastWaiterInner>,
+        /// Registers the receiver while any activity of the application is resumed, and
+        /// unregisters it when no activity is resumed (all are paused, stopped or destroyed).
+        /// This is for receivers meant for foreground-only use, like UI refresh triggers.
+        /// It stops following the lifecycle and unregisters the receiver on dropping the
+        /// returned registration, which cannot outlive this receiver.
+        ///
+        /// Broadcasts sent while the receiver is unregistered (including the short gap between
+        /// pausing and resuming when switching activities) are simply missed.
+        pub fn register_while_resumed(
+            &self,
+            intent_filter: &IntentFilter<'_>,
+        ) -> Result<LifecycleBoundRegistration<'_>, Error> {
+            let state = jni_with_env(|env| {
+                Ok(Arc::new(BoundState {
+                    receiver: env.new_global_ref(&self.receiver)?,
+                    filter: env.new_global_ref(intent_filter)?,
+                    status: Mutex::new(BoundStatus::default()),
+                }))
+            })?;
+            let state_weak = Arc::downgrade(&state);
+            let watcher = LifecycleWatcher::build_with_listener(move |env, is_foreground| {
+                if let Some(state) = state_weak.upgrade() {
+                    let _ = state.update(env, is_foreground);
+                    env.exception_clear();
+                }
+            })?;
+            Ok(LifecycleBoundRegistration {
+                watcher: Some(watcher),
+                state,
+                _receiver: PhantomData,
+            })
+        }
+
+        /// Leaks the Rust handler and returns the global reference of the broadcast
+        /// receiver. It prevents deregistering of the receiver on dropping. This is
+        /// useful if it is created for *once* in the program.
+        pub fn forget(mut self) -> Global<JObject<'static>> {
+            self.forget = true;
+            self.proxy.take().unwrap().forget();
+            jni_with_env(|env| env.new_cast_global_ref::<JObject>(&self.receiver)).unwrap()
+        }
     }
 
+    /// Lifecycle-bound registration created by [BroadcastReceiver::register_while_resumed].
     #[derive(Debug)]
-    struct BroadcastWaiterInner {
-        waker: atomic_waker::AtomicWaker,
-        intents: Mutex<VecDeque<Global<Intent<'static>>>>,
+    pub struct LifecycleBoundRegistration<'a> {
+        watcher: Option<LifecycleWatcher>, // taken on `drop()`
+        state: Arc<BoundState>,
+        _receiver: PhantomData<&'a BroadcastReceiver>,
     }
 
-    impl BroadcastWaiter {
-        /// Creates the waiter with a new broadcast receiver.
-        /// `actions` are passed to `BroadcastReceiver::register_for_action()`.
-        pub fn build(
-            actions: impl IntoIterator<Item = impl AsRef<str>>,
-        ) -> Result<Self, jni::errors::Error> {
-            let inner = Arc::new(BroadcastWaiterInner {
-                waker: atomic_waker::AtomicWaker::new(),
-                intents: Mutex::new(VecDeque::new()),
-            });
-            let inner_weak = Arc::downgrade(&inner);
-            let receiver = BroadcastReceiver::build(move |env, _, intent| {
-                if intent.is_null() {
-                    return Ok(());
-                }
-                let intent = Intent::cast_local(env, intent)?;
-                let Some(inner) = inner_weak.upgrade() else {
-                    return Ok(());
-                };
-                let intent = env.new_global_ref(intent)?;
-                inner.intents.lock().unwrap().push_back(intent);
-                inner.waker.wake();
-                Ok(())
-            })?;
-            for action in actions {
-                receiver.register_for_action(action.as_ref())?;
-            }
-            Ok(Self { receiver, inner })
+    impl LifecycleBoundRegistration<'_> {
+        /// Returns true if the receiver is currently registered.
+        pub fn is_registered(&self) -> bool {
+            self.state.status.lock().unwrap().registered
         }
+    }
 
-        /// Exposes a reference to the broadcast receiver for manual registration.
-        pub fn receiver(&self) -> &BroadcastReceiver {
-            &self.receiver
+    impl Drop for LifecycleBoundRegistration<'_> {
+        fn drop(&mut self) {
+            drop(self.watcher.take());
+            let _ = jni_with_env(|env| self.state.cancel(env));
         }
+    }
 
-        /// Returns the amount of received intents available for checking.
-        pub fn count_received(&self) -> usize {
-            self.inner.intents.lock().unwrap().len()
+    #[derive(Debug)]
+    struct BoundState {
+        receiver: Global<AndroidBroadcastReceiver<'static>>,
+        filter: Global<IntentFilter<'static>>,
+        status: Mutex<BoundStatus>,
+    }
+
+    impl BoundState {
+        // The status lock keeps the receiver from being registered after cancellation.
+        fn update(&self, env: &mut Env<'_>, is_foreground: bool) -> Result<(), Error> {
+            let mut status = self.status.lock().unwrap();
+            let Some(action) = status.on_lifecycle(is_foreground) else {
+                return Ok(());
+            };
+            self.apply(env, action)?;
+            status.done(action);
+            Ok(())
         }
 
-        /// Takes the next received intent if available. This shouldn't conflict
-        /// with the asynchonous feature (which requires a mutable reference).
-        pub fn take_next(&self) -> Option<Global<Intent<'static>>> {
-            self.inner.intents.lock().unwrap().pop_front()
+        fn cancel(&self, env: &mut Env<'_>) -> Result<(), Error> {
+            let mut status = self.status.lock().unwrap();
+            match status.cancel() {
+                Some(action) => self.apply(env, action),
+                None => Ok(()),
+            }
         }
 
-        /// Waits for receiving an intent.
-        /// Note: Waiting in the `android_main()` thread will prevent it from receiving.
-        pub fn wait_timeout(&mut self, timeout: Duration) -> Option<Global<Intent<'static>>> {
-            let fut = BroadcastWaiterFuture { waiter: self };
-            block_with_timeout(fut, timeout).unwrap_or(None)
+        fn apply(&self, env: &mut Env<'_>, action: BoundAction) -> Result<(), Error> {
+            let context = get_android_context();
+            match action {
+                BoundAction::Register => {
+                    context.register_receiver(env, &self.receiver, &self.filter)?;
+                }
+                BoundAction::Unregister => {
+                    context.unregister_receiver(env, &self.receiver)?;
+                }
+            }
+            Ok(())
         }
     }
 
-    /// Convenient blocker for asynchronous functions, based on `futures_lite` and `futures_timer`.
-    /// Warning: Blocking in the `android_main()` thread will block the future's completion if it
-    /// depends on event processing in this thread (check your glue crate like `android_activity`).
-    pub fn block_with_timeout<T>(
-        fut: impl std::future::Future<Output = T>,
-        dur: std::time::Duration,
-    ) -> Option<T> {
-        use futures_lite::{FutureExt, future::block_on};
-        let fut_comp = async { Some(fut.await) };
-        let fut_cancel = async {
-            futures_timer::Delay::new(dur).await;
-            None
+    pub use waiter::*;
+
+    mod waiter {
+        use super::*;
+        use std::{
+            collections::VecDeque,
+            sync::{Arc, Condvar, Mutex},
+            task::Waker,
+            time::Duration,
         };
-        block_on(fut_comp.or(fut_cancel))
-    }
 
-    impl futures_core::Stream for BroadcastWaiter {
-        type Item = Global<Intent<'static>>;
+        /// Waits for intents received by the managed `BroadcastReceiver`.
+        ///
+        /// It implements `futures_core::Stream` with the `futures` feature.
+        #[derive(Debug)]
+        pub struct BroadcastWaiter {
+            receiver: BroadcastReceiver,
+            inner: Arc<BroadcastWaiterInner>,
+        }
 
-        fn poll_next(
-            self: Pin<&mut Self>,
-            cx: &mut task::Context<'_>,
-        ) -> task::Poll<Option<Self::Item>> {
-            // <https://docs.rs/atomic-waker/1.1.2/atomic_waker/struct.AtomicWaker.html#examples>
-            if let Some(intent) = self.take_next() {
-                return task::Poll::Ready(Some(intent));
+        #[derive(Debug, Default)]
+        struct BroadcastWaiterInner {
+            intents: Mutex<VecDeque<Global<Intent<'static>>>>,
+            cond: Condvar,
+            waker: Mutex<Option<Waker>>,
+        }
+
+        impl BroadcastWaiter {
+            /// Creates the waiter with a new broadcast receiver.
+            /// `actions` are passed to `BroadcastReceiver::register_for_action()`.
+            pub fn build(
+                actions: impl IntoIterator<Item = impl AsRef<str>>,
+            ) -> Result<Self, jni::errors::Error> {
+                let inner = Arc::new(BroadcastWaiterInner::default());
+                let inner_weak = Arc::downgrade(&inner);
+                let receiver = BroadcastReceiver::build(move |env, _, intent| {
+                    if intent.is_null() {
+                        return Ok(());
+                    }
+                    let intent = Intent::cast_local(env, intent)?;
+                    let Some(inner) = inner_weak.upgrade() else {
+                        return Ok(());
+                    };
+                    let intent = env.new_global_ref(intent)?;
+                    inner.intents.lock().unwrap().push_back(intent);
+                    inner.cond.notify_all();
+                    if let Some(waker) = inner.waker.lock().unwrap().take() {
+                        waker.wake();
+                    }
+                    Ok(())
+                })?;
+                for action in actions {
+                    receiver.register_for_action(action.as_ref())?;
+                }
+                Ok(Self { receiver, inner })
             }
-            self.inner.waker.register(cx.waker());
-            if let Some(intent) = self.take_next() {
-                task::Poll::Ready(Some(intent))
-            } else {
-                task::Poll::Pending
+
+            /// Exposes a reference to the broadcast receiver for manual registration.
+            pub fn receiver(&self) -> &BroadcastReceiver {
+                &self.receiver
             }
-        }
 
-        // Explanation for this trait function: the actual remaining length should fall
-        // in the returned estimation "range" (min, max).
-        fn size_hint(&self) -> (usize, Option<usize>) {
-            (self.count_received(), None)
-        }
-    }
+            /// Returns the amount of received intents available for checking.
+            pub fn count_received(&self) -> usize {
+                self.inner.intents.lock().unwrap().len()
+            }
 
-    struct BroadcastWaiterFuture<'a> {
-        waiter: &'a mut BroadcastWaiter,
-    }
+            /// Takes the next received intent if available.
+            pub fn take_next(&self) -> Option<Global<Intent<'static>>> {
+                self.inner.intents.lock().unwrap().pop_front()
+            }
 
-    impl<'a> std::future::Future for BroadcastWaiterFuture<'a> {
-        type Output = Option<Global<Intent<'static>>>;
+            /// Waits for receiving an intent.
+            /// Note: Waiting in the `android_main()` thread will prevent it from receiving.
+            pub fn wait_timeout(&self, timeout: Duration) -> Option<Global<Intent<'static>>> {
+                let intents = self.inner.intents.lock().unwrap();
+                let (mut intents, _) = self
+                    .inner
+                    .cond
+                    .wait_timeout_while(intents, timeout, |q| q.is_empty())
+                    .unwrap();
+                intents.pop_front()
+            }
+        }
 
-        fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> task::Poll<Self::Output> {
-            if let task::Poll::Ready(intent) = self.waiter.poll_next(cx) {
-                task::Poll::Ready(intent)
-            } else {
-                task::Poll::Pending
+        #[cfg(feature = "futures")]
+        impl futures_core::Stream for BroadcastWaiter {
+            type Item = Global<Intent<'static>>;
+
+            fn poll_next(
+                self: std::pin::Pin<&mut Self>,
+                cx: &mut std::task::Context<'_>,
+            ) -> std::task::Poll<Option<Self::Item>> {
+                // the waker is registered with the queue locked, so no intent can be missed
+                let mut intents = self.inner.intents.lock().unwrap();
+                if let Some(intent) = intents.pop_front() {
+                    return std::task::Poll::Ready(Some(intent));
+                }
+                self.inner.waker.lock().unwrap().replace(cx.waker().clone());
+                std::task::Poll::Pending
+            }
+
+            // Explanation for this trait function: the actual remaining length should fall
+            // in the returned estimation "range" (min, max).
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.count_received(), None)
             }
         }
     }
 }
+
+/// Convenient blocker for asynchronous functions, parking the current thread until the future
+/// is woken. Returns `None` on timeout.
+/// Warning: Blocking in the `android_main()` thread will block the future's completion if it
+/// depends on event processing in this thread (check your glue crate like `android_activity`).
+#[cfg(feature = "futures")]
+pub fn block_with_timeout<T>(
+    fut: impl std::future::Future<Output = T>,
+    dur: std::time::Duration,
+) -> Option<T> {
+    crate::proxy::block_on_timeout(fut, Some(dur))
+}
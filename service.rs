@@ -0,0 +1,176 @@
+use crate::{
+    android::{android_helper_class_loader, get_android_context},
+    jni_with_env,
+    proxy::DynamicProxy,
+    receiver::Intent,
+};
+
+use jni::{
+    errors::Error,
+    objects::{JClass, JObject},
+    refs::{Global, LoaderContext, Reference},
+};
+
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    task::Waker,
+    time::Duration,
+};
+
+jni::bind_java_type! {
+    pub(crate) ServiceConnection => "android.content.ServiceConnection",
+}
+
+/// State of the connection managed by [ServiceBinding].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServiceState {
+    /// Waiting for `onServiceConnected()`.
+    Connecting,
+    /// `onServiceConnected()` is received, the `IBinder` is available.
+    Connected,
+    /// `onServiceDisconnected()` is received; it may be connected again later.
+    Disconnected,
+    /// `onBindingDied()` is received (API level 28+); the binding should be dropped.
+    BindingDied,
+    /// `onNullBinding()` is received (API level 26+); the service returned a null binder.
+    NullBinding,
+}
+
+/// Binding to an Android `Service` through a `ServiceConnection` backed by `DynamicProxy`.
+/// `Context.unbindService()` is called on `drop()`.
+#[derive(Debug)]
+pub struct ServiceBinding {
+    proxy: DynamicProxy,
+    inner: Arc<BindingInner>,
+}
+
+#[derive(Debug)]
+struct BindingInner {
+    state: Mutex<(ServiceState, Option<Global<JObject<'static>>>)>,
+    cond: Condvar,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl BindingInner {
+    fn set(&self, state: ServiceState, binder: Option<Global<JObject<'static>>>) {
+        *self.state.lock().unwrap() = (state, binder);
+        self.cond.notify_all();
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+impl ServiceBinding {
+    /// `Context.BIND_AUTO_CREATE`: automatically creates the service as long as it is bound.
+    pub const BIND_AUTO_CREATE: i32 = 1;
+
+    /// Calls `Context.bindService()` for the current Android context, with a new
+    /// `ServiceConnection` receiving the state changes.
+    ///
+    /// Returns `Error::NullPtr` if `bindService()` returns false, which means the service
+    /// is not found or the permission is denied.
+    pub fn bind(intent: &Intent<'_>, flags: i32) -> Result<Self, Error> {
+        let inner = Arc::new(BindingInner {
+            state: Mutex::new((ServiceState::Connecting, None)),
+            cond: Condvar::new(),
+            waker: Mutex::new(None),
+        });
+        let inner_weak = Arc::downgrade(&inner);
+        jni_with_env(|env| {
+            let loader = LoaderContext::Loader(android_helper_class_loader()?);
+            let cls_conn = ServiceConnection::lookup_class(env, &loader)?;
+            use std::ops::Deref;
+            let proxy = DynamicProxy::build(
+                env,
+                &loader,
+                [AsRef::<JClass>::as_ref(&cls_conn.deref())],
+                move |env, method, args| {
+                    let Some(inner) = inner_weak.upgrade() else {
+                        return Ok(JObject::null());
+                    };
+                    match method.get_name(env)?.to_string().as_str() {
+                        "onServiceConnected" => {
+                            let binder = args.get_element(env, 1)?;
+                            let binder = env.new_global_ref(binder)?;
+                            inner.set(ServiceState::Connected, Some(binder));
+                        }
+                        "onServiceDisconnected" => inner.set(ServiceState::Disconnected, None),
+                        "onBindingDied" => inner.set(ServiceState::BindingDied, None),
+                        "onNullBinding" => inner.set(ServiceState::NullBinding, None),
+                        _ => (),
+                    }
+                    Ok(JObject::null())
+                },
+            )?;
+
+            let context = get_android_context();
+            let conn = env.as_cast::<ServiceConnection>(proxy.as_ref())?;
+            if !context.bind_service(env, intent, &conn, flags)? {
+                // the connection should be released even if it failed
+                let _ = context.unbind_service(env, &conn);
+                return Err(Error::NullPtr("`Context.bindService()` returned false"));
+            }
+            Ok(Self { proxy, inner })
+        })
+    }
+
+    /// Gets the current state of the connection.
+    pub fn state(&self) -> ServiceState {
+        self.inner.state.lock().unwrap().0
+    }
+
+    /// Gets the `IBinder` of the service if it is connected.
+    pub fn binder(&self) -> Option<Global<JObject<'static>>> {
+        let state = self.inner.state.lock().unwrap();
+        let binder = state.1.as_ref()?;
+        jni_with_env(|env| env.new_global_ref(binder)).ok()
+    }
+
+    /// Blocks until the state is no longer `Connecting` or the timeout elapses, then returns
+    /// the `IBinder` if it is connected.
+    ///
+    /// Note: Waiting in the `android_main()` thread will prevent it from being connected,
+    /// because `ServiceConnection` callbacks are called in the main thread.
+    pub fn wait_connected(&self, timeout: Duration) -> Option<Global<JObject<'static>>> {
+        let state = self.inner.state.lock().unwrap();
+        let _ = self
+            .inner
+            .cond
+            .wait_timeout_while(state, timeout, |s| s.0 == ServiceState::Connecting)
+            .unwrap();
+        self.binder()
+    }
+
+    /// Asynchronous version of [ServiceBinding::wait_connected] without a timeout.
+    #[cfg(feature = "futures")]
+    pub async fn connected(&self) -> Option<Global<JObject<'static>>> {
+        std::future::poll_fn(|cx| {
+            // the waker is registered with the state locked, so no change can be missed
+            let state = self.inner.state.lock().unwrap();
+            if state.0 == ServiceState::Connecting {
+                self.inner.waker.lock().unwrap().replace(cx.waker().clone());
+                std::task::Poll::Pending
+            } else {
+                std::task::Poll::Ready(())
+            }
+        })
+        .await;
+        self.binder()
+    }
+}
+
+impl Drop for ServiceBinding {
+    fn drop(&mut self) {
+        let _ = jni_with_env(|env| {
+            let conn = env.as_cast::<ServiceConnection>(self.proxy.as_ref())?;
+            get_android_context().unbind_service(env, &conn)
+        });
+    }
+}
+
+/// Binds to an Android `Service`, check [ServiceBinding::bind].
+#[inline(always)]
+pub fn bind_service(intent: &Intent<'_>, flags: i32) -> Result<ServiceBinding, Error> {
+    ServiceBinding::bind(intent, flags)
+}
@@ -0,0 +1,199 @@
+use crate::{
+    android::{
+        AndroidContext, AndroidFeature, android_feature_available, context_is_activity,
+        get_android_context, get_application_context,
+    },
+    jni_with_env,
+    proxy::DynamicProxy,
+    receiver::Intent,
+    uri::AndroidUri,
+};
+
+use jni::{Env, errors::Error, objects::JString};
+
+jni::bind_java_type! {
+    AndroidToast => "android.widget.Toast",
+    type_map = {
+        AndroidContext => "android.content.Context",
+    },
+    methods {
+        static fn make_text(context: AndroidContext, text: JCharSequence, duration: jint) -> AndroidToast,
+        fn show(),
+    },
+}
+
+const TOAST_LENGTH_SHORT: i32 = 0;
+const TOAST_LENGTH_LONG: i32 = 1;
+
+/// Shows a toast message with the application context. It is posted to the main looper,
+/// so it can be called from any thread; returns after posting, and failures on the main
+/// thread are logged as warnings.
+///
+/// It doesn't panic if the Android context is not initialized; `Error::NullPtr` is returned
+/// with a warning in that case.
+pub fn android_toast(text: &str, long: bool) -> Result<(), Error> {
+    // `jni_with_env` panics if the `JavaVM` can't be obtained from `jni` or `ndk_context`
+    if std::panic::catch_unwind(crate::jni_get_vm).is_err() {
+        warn!("android_toast: the Android context is not ready.");
+        return Err(Error::NullPtr("Android context is not ready"));
+    }
+    let text = text.to_string();
+    let duration = if long {
+        TOAST_LENGTH_LONG
+    } else {
+        TOAST_LENGTH_SHORT
+    };
+    let res = DynamicProxy::post_to_main_looper(move |env| {
+        let res = (|| {
+            let context = get_application_context()?;
+            let text = JString::new(env, &text)?;
+            let toast = AndroidToast::make_text(env, context, text.as_char_sequence(), duration)?;
+            toast.show(env)
+        })();
+        if let Err(e) = &res {
+            warn!("android_toast: {e}");
+        }
+        res
+    });
+    match res {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            warn!("android_toast: failed to post to the main looper.");
+            Err(Error::NullPtr("Handler.post() returned false"))
+        }
+        Err(e) => {
+            warn!("android_toast: {e}");
+            Err(e)
+        }
+    }
+}
+
+jni::bind_java_type! {
+    AndroidWindowManagerApi30 => "android.view.WindowManager",
+    type_map = {
+        AndroidWindowMetrics => "android.view.WindowMetrics",
+    },
+    methods {
+        fn get_current_window_metrics() -> AndroidWindowMetrics,
+    },
+}
+
+jni::bind_java_type! {
+    AndroidWindowMetrics => "android.view.WindowMetrics",
+    type_map = {
+        AndroidRect => "android.graphics.Rect",
+    },
+    methods {
+        fn get_bounds() -> AndroidRect,
+    },
+}
+
+jni::bind_java_type! {
+    AndroidRect => "android.graphics.Rect",
+    methods {
+        fn width() -> jint,
+        fn height() -> jint,
+    },
+}
+
+/// Display metrics returned by [android_display_metrics].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DisplayMetrics {
+    pub width_px: i32,
+    pub height_px: i32,
+    /// Logical density, the scaling factor of density-independent pixels (1.0 for 160 dpi).
+    pub density: f32,
+    pub density_dpi: i32,
+}
+
+/// Gets the size and density of the display area available for the current context.
+///
+/// The density is read from `Resources.getDisplayMetrics()`. If the context is an activity on
+/// API level 30 or above, the size is the bounds of `WindowManager.getCurrentWindowMetrics()`
+/// (including system bars), replacing the deprecated `Display` metrics; otherwise, it is read
+/// from the same `DisplayMetrics` of the resources. Values are not cached, so they follow
+/// configuration changes like screen rotation.
+pub fn android_display_metrics() -> Result<DisplayMetrics, Error> {
+    jni_with_env(|env| {
+        let context = get_android_context();
+        let metrics = context.get_resources(env)?.get_display_metrics(env)?;
+        let mut result = DisplayMetrics {
+            width_px: metrics.width_pixels(env)?,
+            height_px: metrics.height_pixels(env)?,
+            density: metrics.density(env)?,
+            density_dpi: metrics.density_dpi(env)?,
+        };
+        if android_feature_available(AndroidFeature::WindowMetrics) && context_is_activity(env)? {
+            let service_name = JString::new(env, "window")?;
+            let window_manager = context.get_system_service(env, service_name)?;
+            if !window_manager.is_null() {
+                let window_manager = env.cast_local::<AndroidWindowManagerApi30>(window_manager)?;
+                let bounds = window_manager
+                    .get_current_window_metrics(env)?
+                    .get_bounds(env)?;
+                result.width_px = bounds.width(env)?;
+                result.height_px = bounds.height(env)?;
+            }
+        }
+        Ok(result)
+    })
+}
+
+const ACTION_VIEW: &str = "android.intent.action.VIEW";
+const ACTION_SEND: &str = "android.intent.action.SEND";
+const EXTRA_SUBJECT: &str = "android.intent.extra.SUBJECT";
+const EXTRA_TEXT: &str = "android.intent.extra.TEXT";
+const FLAG_ACTIVITY_NEW_TASK: i32 = 0x10000000;
+
+/// Opens the URL (or any URI) with an `ACTION_VIEW` intent, usually in the browser.
+/// Returns false if no activity is found to handle the intent.
+pub fn android_open_url(url: &str) -> Result<bool, Error> {
+    jni_with_env(|env| {
+        let action = JString::new(env, ACTION_VIEW)?;
+        let uri = AndroidUri::new(env, url)?;
+        let intent = Intent::new_with_action_uri(env, action, uri)?;
+        start_activity_checked(env, &intent)
+    })
+}
+
+/// Shares the plain text with an `ACTION_SEND` intent wrapped by the system chooser.
+/// Returns false if no activity is found to handle the intent.
+pub fn android_share_text(subject: Option<&str>, text: &str) -> Result<bool, Error> {
+    jni_with_env(|env| {
+        let action = JString::new(env, ACTION_SEND)?;
+        let intent = Intent::new_with_action(env, action)?;
+        let mime_type = JString::new(env, "text/plain")?;
+        intent.set_type(env, mime_type)?;
+        if let Some(subject) = subject {
+            let extra_subject = JString::new(env, EXTRA_SUBJECT)?;
+            let subject = JString::new(env, subject)?;
+            intent.put_extra_string(env, extra_subject, subject)?;
+        }
+        let extra_text = JString::new(env, EXTRA_TEXT)?;
+        let text = JString::new(env, text)?;
+        intent.put_extra_string(env, extra_text, text)?;
+        let chooser = Intent::create_chooser(env, &intent, JString::null().as_char_sequence())?;
+        start_activity_checked(env, &chooser)
+    })
+}
+
+// Starts the activity from the current context, adding `FLAG_ACTIVITY_NEW_TASK` if the context
+// is not an activity; returns false if `ActivityNotFoundException` is caught.
+fn start_activity_checked(env: &mut Env<'_>, intent: &Intent<'_>) -> Result<bool, Error> {
+    if !context_is_activity(env)? {
+        intent.add_flags(env, FLAG_ACTIVITY_NEW_TASK)?;
+    }
+    match get_android_context().start_activity(env, intent) {
+        Err(Error::JavaException) => match env.exception_catch() {
+            Err(Error::CaughtJavaException { name, .. })
+                if name == "android.content.ActivityNotFoundException" =>
+            {
+                warn!("No activity found to handle the intent.");
+                Ok(false)
+            }
+            Err(e) => Err(e),
+            Ok(()) => Err(Error::JavaException),
+        },
+        res => res.map(|_| true),
+    }
+}
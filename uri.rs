@@ -0,0 +1,111 @@
+use crate::android::{AndroidContext, JFile, get_android_context};
+
+use jni::{
+    Env,
+    errors::Error,
+    objects::{JClass, JObject, JString},
+    refs::{LoaderContext, Reference},
+};
+
+jni::bind_java_type! {
+    pub AndroidUri => "android.net.Uri",
+    type_map = {
+        JFile => "java.io.File",
+    },
+    methods {
+        static fn parse(uri_string: JString) -> AndroidUri,
+        static fn from_file(file: JFile) -> AndroidUri,
+        fn get_scheme() -> JString,
+        fn get_authority() -> JString,
+        fn get_path() -> JString,
+        fn get_query_parameter(key: JString) -> JString,
+        fn to_string() -> JString,
+    },
+}
+
+jni::bind_java_type! {
+    FileProvider => "androidx.core.content.FileProvider",
+    type_map = {
+        AndroidContext => "android.content.Context",
+        AndroidUri => "android.net.Uri",
+        JFile => "java.io.File",
+    },
+    methods {
+        static fn get_uri_for_file(context: AndroidContext, authority: JString, file: JFile) -> AndroidUri,
+    },
+}
+
+impl<'local> AndroidUri<'local> {
+    /// Parses the URI string like `content://settings/system` by `Uri.parse()`.
+    pub fn new(env: &mut Env<'local>, uri: &str) -> Result<Self, Error> {
+        let uri = JString::new(env, uri)?;
+        AndroidUri::parse(env, uri)
+    }
+
+    /// Creates the URI of a local file path. Without `authority`, it is a `file://` URI by
+    /// `Uri.fromFile()`, which causes `FileUriExposedException` when it is passed to another
+    /// app on API level 24 and above. With `authority`, it is a `content://` URI created by
+    /// `androidx.core.content.FileProvider` of that authority, which must be declared in the
+    /// `AndroidManifest.xml` with paths covering the file; the `androidx.core` library is
+    /// required in the package.
+    pub fn for_file(
+        env: &mut Env<'local>,
+        path: &str,
+        authority: Option<&str>,
+    ) -> Result<Self, Error> {
+        let path = JString::new(env, path)?;
+        let file = JFile::new(env, path)?;
+        if let Some(authority) = authority {
+            let authority = JString::new(env, authority)?;
+            let context = get_android_context();
+            // the class is in the application's dex, which is not visible to the system loader
+            let _ = FileProviderAPI::get(env, &LoaderContext::FromObject(context))?;
+            FileProvider::get_uri_for_file(env, context, authority, file)
+        } else {
+            AndroidUri::from_file(env, file)
+        }
+    }
+
+    /// Returns the scheme like `content`, or `None` for a relative URI.
+    pub fn scheme(&self, env: &mut Env<'_>) -> Result<Option<String>, Error> {
+        let scheme = self.get_scheme(env)?;
+        Ok((!scheme.is_null()).then(|| scheme.to_string()))
+    }
+
+    /// Returns the decoded authority like `settings`, or `None` if it is absent.
+    pub fn authority(&self, env: &mut Env<'_>) -> Result<Option<String>, Error> {
+        let authority = self.get_authority(env)?;
+        Ok((!authority.is_null()).then(|| authority.to_string()))
+    }
+
+    /// Returns the decoded path like `/system`, or `None` if it is absent.
+    pub fn path(&self, env: &mut Env<'_>) -> Result<Option<String>, Error> {
+        let path = self.get_path(env)?;
+        Ok((!path.is_null()).then(|| path.to_string()))
+    }
+
+    /// Returns the first decoded value of the query parameter, or `None` if it is absent.
+    /// `Error::JavaException` is returned for non-hierarchical URIs like `mailto:`.
+    pub fn query_parameter(&self, env: &mut Env<'_>, key: &str) -> Result<Option<String>, Error> {
+        let key = JString::new(env, key)?;
+        let value = self.get_query_parameter(env, key)?;
+        Ok((!value.is_null()).then(|| value.to_string()))
+    }
+}
+
+impl AndroidUri<'_> {
+    /// Reads the string form of `obj` if it is an `android.net.Uri`, which may be an argument
+    /// received by a [crate::DynamicProxy] handler or an extra of an `Intent`.
+    /// Returns `None` for null or objects of other classes.
+    pub fn string_of(env: &mut Env<'_>, obj: &JObject<'_>) -> Result<Option<String>, Error> {
+        if obj.is_null() {
+            return Ok(None);
+        }
+        let class = AndroidUri::lookup_class(env, &LoaderContext::None)?;
+        if !env.is_instance_of(obj, AsRef::<JClass>::as_ref(&*class))? {
+            return Ok(None);
+        }
+        let uri = env.as_cast::<AndroidUri>(obj)?;
+        Ok(Some(uri.to_string(env)?.to_string()))
+    }
+}